@@ -1,11 +1,37 @@
 // Find all our documentation at https://docs.near.org
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::env::{self, log_str};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
 use std::collections::{BinaryHeap, HashMap};
 
+const DEFAULT_REQUIRED_ENDORSEMENTS: u32 = 2;
+const DEFAULT_QUORUM: u32 = 2;
+const APPEAL_PANEL_SIZE: usize = 3;
+const MIN_SCORE: u8 = 1;
+const MAX_SCORE: u8 = 10;
+const MIN_CONFIDENCE: u8 = 1;
+const MAX_CONFIDENCE: u8 = 5;
+const DEFAULT_CONFIDENCE: u8 = 3; // Weight of a vote revealed without a confidence level
+const TRUTHFULNESS_SCALE: f64 = 1000.0; // Truthfulness scores are stored as integers in thousandths
+const DEFAULT_COMMIT_WINDOW: u64 = 3 * 24 * 60 * 60 * 1_000_000_000; // 3 days in nanoseconds
+const DEFAULT_REBUTTAL_WINDOW: u64 = 2 * 24 * 60 * 60 * 1_000_000_000; // 2 days in nanoseconds
+const DEFAULT_REVEAL_WINDOW: u64 = 2 * 24 * 60 * 60 * 1_000_000_000; // 2 days in nanoseconds
+const DEFAULT_COMMENT_COMMIT_WINDOW: u64 = 3 * 24 * 60 * 60 * 1_000_000_000; // 3 days in nanoseconds
+const DEFAULT_COMMENT_REVEAL_WINDOW: u64 = 2 * 24 * 60 * 60 * 1_000_000_000; // 2 days in nanoseconds
+const DEFAULT_UNBONDING_PERIOD: u64 = 7 * 24 * 60 * 60 * 1_000_000_000; // 7 days in nanoseconds
+const DEFAULT_SLASH_PERCENT: u8 = 10;
+const DEFAULT_REJECT_REFUND_PERCENT: u8 = 50;
+const DEFAULT_EDITOR_WINDOW: u64 = 3 * 24 * 60 * 60 * 1_000_000_000; // 3 days in nanoseconds
+const GAS_FOR_REWARD_CALLBACK: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
+const STORAGE_BALANCE_MIN: Balance = 10_000_000_000_000_000_000_000; // 0.01 NEAR
+const X25519_KEY_LENGTH: usize = 32; // Bytes in an X25519 public key
+const NONCE_LENGTH: usize = 24; // Bytes in an XSalsa20-Poly1305 nonce
+
 #[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct VoteCommit {
@@ -16,18 +42,123 @@ pub struct VoteCommit {
 #[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct CommentCommit {
+    comment_id: u64,
+    reviewer: String,
+    commit: String,         // Hash of the comment
+    parent_id: Option<u64>, // Comment this one replies to, if any
+}
+
+// Define the Comment structure, a revealed comment in a submission's discussion
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Comment {
+    comment_id: u64,
+    commenter: String, // Reviewer of a revealed comment, or the poster of a public comment
+    text: String,
+    parent_id: Option<u64>,
+}
+
+// Define the EncryptedComment structure, a comment only the submission's author can decrypt
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EncryptedComment {
+    comment_id: u64,
     reviewer: String,
-    commit: String, // Hash of the comment
+    author_key: String, // Author's X25519 public key at the time of encryption, in hex
+    ciphertext: String, // In hex
+    nonce: String,      // In hex
+}
+
+// Define the Ballot structure, everything a reviewer commits to under a single commitment
+#[derive(
+    Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Ballot {
+    vote: String, // "accept" or "reject"
+    #[serde(default)]
+    scores: Vec<u8>, // One score per criterion of the submission's scoring rubric
+    #[serde(default)]
+    confidence: Option<u8>, // Self-reported confidence from MIN_CONFIDENCE to MAX_CONFIDENCE
+    #[serde(default)]
+    prediction: Option<u8>, // Predicted percentage of the panel that will vote "accept"
+}
+
+impl Ballot {
+    // Builds the text hashed into the commitment; a plain vote hashes exactly like before
+    fn preimage(&self) -> String {
+        let mut preimage = self.vote.clone();
+        if !self.scores.is_empty() {
+            let scores: Vec<String> = self.scores.iter().map(|score| score.to_string()).collect();
+            preimage.push_str(&format!("|{}", scores.join(",")));
+        }
+        if let Some(confidence) = self.confidence {
+            preimage.push_str(&format!("|confidence={}", confidence));
+        }
+        if let Some(prediction) = self.prediction {
+            preimage.push_str(&format!("|prediction={}", prediction));
+        }
+        preimage
+    }
 }
 
+// Define the CriterionAggregate structure, summarizing the revealed scores for one criterion
 #[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
+pub struct CriterionAggregate {
+    criterion: String,
+    mean: u32,   // Scaled by 100 to avoid floating point
+    median: u32, // Scaled by 100 to avoid floating point
+    spread: u8,  // Highest minus lowest score
+}
+
+#[derive(
+    Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
 pub struct SubmissionVote {
     submission_id: u64,
     vote_commits: Vec<VoteCommit>,
     revealed_votes: HashMap<String, String>, // Maps reviewer names to their votes ("accept" or "reject")
     comment_commits: Vec<CommentCommit>,     // Holds commits for comments
-    revealed_comments: HashMap<String, String>, // Maps reviewer names to their comments
+    revealed_comments: Vec<Comment>,         // Comments in the order they were revealed
+    revealed_scores: HashMap<String, Vec<u8>>, // Maps reviewer names to their rubric scores
+    revealed_confidences: HashMap<String, u8>, // Maps reviewer names to their confidence level
+    rebuttal_deadline: Option<u64>,          // Set when the author opens the rebuttal window
+    rebuttals: HashMap<u64, String>,         // Maps comment IDs to the author's response
+    vote_update_commits: Vec<VoteCommit>,    // Commits for votes updated after the rebuttal
+    revealed_predictions: HashMap<String, u8>, // Maps reviewer names to their predicted accept percentage
+    truthfulness_scores: HashMap<String, i64>, // Peer-prediction score per reviewer, set at finalization
+    forfeited_reviewers: Vec<String>, // Reviewers whose commit was not revealed before the reveal deadline
+    public_comments: Vec<Comment>,    // Uncommitted comments posted once a decision exists
+    next_comment_id: u64,             // Shared by committed, public and encrypted comments
+    encrypted_comments: Vec<EncryptedComment>, // Comments addressed to the author only
+    update_reveal_deadline: Option<u64>, // Vote updates must be revealed by then
+    revealed_updates: Vec<String>,    // Reviewers whose vote update was revealed
+}
+
+impl SubmissionVote {
+    // Returns whether the author's rebuttal window is currently open
+    fn rebuttal_open(&self) -> bool {
+        self.rebuttal_deadline
+            .is_some_and(|deadline| env::block_timestamp() <= deadline)
+    }
+
+    // Returns whether a rebuttal window was opened and has since closed
+    fn rebuttal_closed(&self) -> bool {
+        self.rebuttal_deadline
+            .is_some_and(|deadline| env::block_timestamp() > deadline)
+    }
+
+    // Returns whether committed vote updates are still unrevealed and may be revealed
+    fn awaiting_vote_updates(&self) -> bool {
+        self.update_reveal_deadline
+            .is_some_and(|deadline| env::block_timestamp() <= deadline)
+            && self
+                .vote_update_commits
+                .iter()
+                .any(|vc| !self.revealed_updates.contains(&vc.reviewer))
+    }
 }
 
 // Define the Reviewer structure
@@ -36,18 +167,476 @@ pub struct SubmissionVote {
 pub struct Reviewer {
     name: String,
     keywords: Vec<String>,
+    no_shows: u32, // Number of times the reviewer was replaced for missing a commit window
+    truthfulness_score: i64, // Sum of peer-prediction scores, in thousandths
+    scored_reviews: u32, // Number of reviews that received a peer-prediction score
+    unrevealed_commits: u32, // Number of committed votes forfeited for missing the reveal deadline
+    stake: Balance, // Staked deposit, required for assignment and slashable
+    unbonding: Balance, // Unstaked funds that are still slashable until unbonding_until
+    unbonding_until: u64,
+}
+
+// Status of a misconduct report filed against a reviewer
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReportStatus {
+    Pending,
+    Upheld,
+    Dismissed,
+}
+
+// Define the MisconductReport structure, a complaint that can lead to slashing a reviewer's stake
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MisconductReport {
+    reporter: String,
+    reviewer: String,
+    submission_id: u64,
+    reason: String,
+    status: ReportStatus,
+    slashed: U128, // Stake removed when the report was upheld
+}
+
+// Status of a reviewer self-registration application
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ApplicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+// Define the ReviewerApplication structure
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReviewerApplication {
+    applicant: String,
+    keywords: Vec<String>,
+    credentials: String, // Free-form credentials, e.g. affiliation or a link to publications
+    endorsements: Vec<String>, // Reviewers who endorsed the application
+    rejections: Vec<String>, // Reviewers who rejected the application
+    status: ApplicationStatus,
+}
+use near_sdk::{
+    assert_one_yocto, ext_contract, near_bindgen, AccountId, Balance, Gas, Promise, PromiseError,
+    PromiseOrValue,
+};
+
+// Share of revealed votes that must be "accept" for a submission to be accepted
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VoteThreshold {
+    Unanimous,
+    SimpleMajority,
+    Supermajority { numerator: u32, denominator: u32 },
+}
+
+// Define the DecisionRule structure, applied when a submission is finalized
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DecisionRule {
+    threshold: VoteThreshold,
+    quorum: u32, // Minimum number of revealed votes
+    #[serde(default)]
+    weight_by_confidence: bool, // Weigh each vote by its confidence instead of counting it once
+}
+
+impl Default for DecisionRule {
+    fn default() -> Self {
+        Self {
+            threshold: VoteThreshold::Unanimous,
+            quorum: DEFAULT_QUORUM,
+            weight_by_confidence: false,
+        }
+    }
+}
+
+impl DecisionRule {
+    // Checks that at least one reveal is required and that a supermajority is a proper fraction
+    fn is_valid(&self) -> bool {
+        if self.quorum == 0 {
+            return false;
+        }
+        match self.threshold {
+            VoteThreshold::Supermajority {
+                numerator,
+                denominator,
+            } => numerator > 0 && numerator <= denominator,
+            _ => true,
+        }
+    }
+
+    // Returns whether the given (possibly weighted) tally meets the threshold
+    fn accepts(&self, accept_weight: u32, total_weight: u32) -> bool {
+        match self.threshold {
+            VoteThreshold::Unanimous => accept_weight == total_weight,
+            VoteThreshold::SimpleMajority => accept_weight * 2 > total_weight,
+            VoteThreshold::Supermajority {
+                numerator,
+                denominator,
+            } => {
+                accept_weight as u64 * denominator as u64 >= numerator as u64 * total_weight as u64
+            }
+        }
+    }
+}
+
+// How a submission's review fee is split among the reviewers who revealed
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RewardFormula {
+    EqualSplit,
+    OutcomeAlignment, // Reviewers whose vote matches the outcome get a double share
+}
+
+// Result of applying a decision rule to the revealed votes
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DecisionOutcome {
+    Accepted,
+    Rejected,
+    NoQuorum,
+}
+
+// Define the Decision structure, recording how a submission was decided
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Decision {
+    rule: DecisionRule,
+    accept_votes: u32,
+    reject_votes: u32,
+    abstentions: u32,                   // Committed votes that were never revealed
+    vote_weights: HashMap<String, u32>, // Weight each reviewer's vote carried in the tally
+    accept_weight: u32,
+    reject_weight: u32,
+    outcome: DecisionOutcome,
+}
+
+// Final call an editor can make on a submission
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EditorVerdict {
+    Accept,
+    Reject,
+    Revise,
+}
+
+// Define the EditorDecision structure, recording an editor's final call on a submission
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EditorDecision {
+    editor: String,
+    verdict: EditorVerdict,
+    justification: String,
+    overrides_mechanical: bool, // Set when the verdict differs from the decision rule's outcome
 }
-use near_sdk::near_bindgen;
 
+// Define the Annotation structure, a reviewer comment anchored to a range of a version's text
 #[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
+pub struct Annotation {
+    annotation_id: u64,
+    reviewer: String,
+    round: u32, // Review round whose version of the text is annotated
+    start: u32, // Offset of the first annotated character
+    end: u32,   // Offset just past the last annotated character
+    text: String,
+}
+
+// Define the ReviewRound structure, an archived snapshot of one round of review
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReviewRound {
+    round: u32,
+    response: String,
+    submission_votes: SubmissionVote,
+    decision: Option<Decision>,
+    score_aggregates: Vec<CriterionAggregate>,
+    editor_decision: Option<EditorDecision>,
+}
+
+// Result of an appeal against a rejection
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum AppealOutcome {
+    Upheld,
+    Overturned,
+}
+
+// Define the Appeal structure, a fresh commit-reveal vote on a rejected submission
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Appeal {
+    justification: String,
+    deposit: U128,      // Attached by the author when filing the appeal
+    panel: Vec<String>, // Reviewers drawn for the appeal, excluding the original reviewers
+    vote_commits: Vec<VoteCommit>,
+    revealed_votes: HashMap<String, String>, // Maps reviewer names to "uphold" or "overturn"
+    outcome: Option<AppealOutcome>,
+    refunded: U128,       // Part of the deposit returned to the author
+    commit_deadline: u64, // Panel members commit until then
+    reveal_deadline: u64, // After this, missing commits and reveals count as abstentions
+}
+
+// Define the RefundPolicy structure, deciding how much of the author's deposit is returned
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RefundPolicy {
+    accept_refund_percent: u8, // Refunded when the submission is accepted
+    reject_refund_percent: u8, // Refunded on a rejection or a desk reject that is not spam
+}
+
+impl Default for RefundPolicy {
+    fn default() -> Self {
+        Self {
+            accept_refund_percent: 100,
+            reject_refund_percent: DEFAULT_REJECT_REFUND_PERCENT,
+        }
+    }
+}
+
+// Define the DeskRejection structure, an editor rejecting a submission before review
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DeskRejection {
+    editor: String,
+    justification: String,
+    spam: bool, // Spam forfeits the whole deposit to the treasury
+}
+
+// Define the Track structure, holding per-track configuration
+#[derive(
+    Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Track {
+    decision_rule: Option<DecisionRule>, // Falls back to the contract-wide rule when unset
+    editor: Option<String>,              // Editor for submissions without their own editor
+    refund_policy: Option<RefundPolicy>, // Falls back to the contract-wide policy when unset
+}
+
+#[derive(
+    Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug, Default,
+)]
+#[serde(crate = "near_sdk::serde")]
 pub struct Submission {
     author: String,
     response: String,
     suggested_reviewers: Vec<String>,
     submission_votes: SubmissionVote,
-    voting_ended: bool,     // Flag to indicate if voting has ended
+    voting_ended: bool,           // Flag to indicate if voting has ended
+    reveal_deadline: Option<u64>, // Set when voting ends; later reveals are rejected
+    // Comments are committed until this deadline and revealed afterwards, independently of votes
+    comment_commit_deadline: u64,
+    comment_reveal_deadline: u64,
     accepted: Option<bool>, // New field to indicate if the submission is accepted or rejected
+    // Maps assigned reviewers to their commit deadline
+    commit_deadlines: HashMap<String, u64>,
+    // Reviewers removed from the panel for missing their commit deadline
+    replaced_reviewers: Vec<String>,
+    track: Option<String>,
+    decision: Option<Decision>, // Tally and rule recorded when the submission is finalized
+    scoring_rubric: Vec<String>, // Criteria reviewers score, fixed when the submission is created
+    score_aggregates: Vec<CriterionAggregate>, // Per-criterion results computed at finalization
+    editor: Option<String>,     // Overrides the track's editor for this submission
+    editor_decision: Option<EditorDecision>,
+    // Current review round, starting at 1
+    round: u32,
+    // Set when the editor asks the author for a new version
+    revisions_requested: bool,
+    // Earlier review rounds, oldest first
+    history: Vec<ReviewRound>,
+    appeal: Option<Appeal>,
+    // Storage cost charged to the author's storage balance at submission time
+    storage_cost: Balance,
+    // Part of the fee held in escrow for the reviewers
+    review_fee: Balance,
+    // Maps reviewers to the reward sent to them at finalization
+    reward_payouts: HashMap<String, Balance>,
+    // Part of the review fee returned to the author
+    reward_refunded: Balance,
+    // NEP-141 token the review fee was paid in; NEAR when unset
+    fee_token: Option<String>,
+    // Refundable deposit attached by the author, settled by the refund policy
+    deposit: Balance,
+    deposit_refunded: Balance,
+    // Part of the deposit paid to the reviewers instead of the author
+    deposit_forfeited: Balance,
+    desk_rejection: Option<DeskRejection>,
+    // Set once the review fee has been paid out or refunded
+    fees_settled: bool,
+    // Editor decisions and appeals are accepted until then; set when the submission is finalized
+    editor_deadline: Option<u64>,
+    // Set once the deposit has been refunded or forfeited on the final outcome
+    deposit_settled: bool,
+    // Inline annotations across every round, in the order they were added
+    annotations: Vec<Annotation>,
+}
+
+impl Submission {
+    // Returns whether some committed votes are unrevealed while the reveal window is still open
+    fn reveals_pending(&self) -> bool {
+        !self.reveal_closed()
+            && self.submission_votes.vote_commits.iter().any(|vc| {
+                !self
+                    .submission_votes
+                    .revealed_votes
+                    .contains_key(&vc.reviewer)
+            })
+    }
+
+    // Returns whether the submission was finalized and its editor window is still open
+    fn editor_window_open(&self) -> bool {
+        self.editor_deadline
+            .is_some_and(|deadline| env::block_timestamp() <= deadline)
+    }
+
+    // Returns whether the submission was finalized and its editor window has since closed
+    fn editor_window_closed(&self) -> bool {
+        self.editor_deadline
+            .is_some_and(|deadline| env::block_timestamp() > deadline)
+    }
+
+    // Returns whether voting ended and the reveal window has since closed
+    fn reveal_closed(&self) -> bool {
+        self.reveal_deadline
+            .is_some_and(|deadline| env::block_timestamp() > deadline)
+    }
+}
+
+// Kind of movement recorded in a bounty pool's history
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PoolEntryKind {
+    Deposit,
+    Payout,
+    Refund, // A failed payout returned to the pool
+}
+
+// Define the PoolEntry structure, one movement of funds in or out of a bounty pool
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolEntry {
+    kind: PoolEntryKind,
+    account: String, // Sponsor for deposits, reviewer for payouts and refunds
+    submission_id: Option<u64>,
+    amount: U128,
+    timestamp: u64,
+}
+
+// Define the BountyPool structure, sponsor funds paying reviewers of matching submissions
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BountyPool {
+    sponsor: String,
+    keywords: Vec<String>, // A submission matches if its text contains any of these keywords
+    track: Option<String>, // A submission to this track always matches
+    reward_per_submission: U128, // Drawn from the pool each time a matching submission is finalized
+    balance: U128,
+    history: Vec<PoolEntry>,
+    token: Option<String>, // NEP-141 token the pool holds; NEAR when unset
+}
+
+// Actions that can be requested in the msg of a NEP-141 ft_transfer_call to this contract
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+pub enum TokenAction {
+    Submit {
+        data: String,
+        track: Option<String>,
+    },
+    CreatePool {
+        keywords: Vec<String>,
+        track: Option<String>,
+        reward_per_submission: U128,
+    },
+    FundPool {
+        pool_id: u64,
+    },
+}
+
+// Interface of the NEP-141 fungible token contracts used for payouts
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+// Kind of funds movement recorded in the treasury ledger
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LedgerKind {
+    FeeCollected,     // Review fee paid by an author
+    DepositCollected, // Refundable submission or appeal deposit paid by an author
+    RewardPaid,       // Review fee, bounty or forfeited deposit sent to a reviewer
+    RewardReturned,   // A reward transfer that failed and came back
+    Refunded,         // Funds returned to an author
+    StakeAdded,
+    StakeWithdrawn,
+    Slashed,
+    BountyFunded,
+    StorageDeposited,
+    StorageWithdrawn,
+}
+
+// Define the LedgerEntry structure, one movement of funds into or out of the contract
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LedgerEntry {
+    kind: LedgerKind,
+    account: String,
+    submission_id: Option<u64>,
+    amount: U128,
+    token: Option<String>, // NEP-141 token of the amount; NEAR when unset
+    timestamp: u64,
+}
+
+impl LedgerEntry {
+    fn new(
+        kind: LedgerKind,
+        account: &str,
+        submission_id: Option<u64>,
+        amount: Balance,
+        token: Option<&String>,
+    ) -> Self {
+        Self {
+            kind,
+            account: account.to_string(),
+            submission_id,
+            amount: U128(amount),
+            token: token.cloned(),
+            timestamp: env::block_timestamp(),
+        }
+    }
+}
+
+// Define the TreasuryReport structure, reconciling the NEAR the contract owes with its balance
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TreasuryReport {
+    account_balance: U128, // As reported by env::account_balance()
+    stakes: U128,          // Staked and unbonding reviewer funds
+    storage_deposits: U128,
+    bounty_pools: U128,
+    escrow: U128,  // Review fees and deposits of submissions and appeals not settled yet
+    slashed: U128, // Slashed stake and forfeited deposits kept by the contract
+    surplus: U128, // Balance left after every obligation; zero when not solvent
+    solvent: bool,
+}
+
+// NEP-145 storage balance of an account
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    total: U128,
+    available: U128,
+}
+
+// NEP-145 bounds on the storage balance an account can hold
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    min: U128,
+    max: Option<U128>,
 }
 
 // Define the contract structure
@@ -61,7 +650,53 @@ pub struct Contract {
     authors: Vec<String>,
     reviewers: Vec<Reviewer>,
     submissions: Vec<Submission>, // Added submissions vector
-                                  // submission_votes: Vec<SubmissionVote>, // Added to store votes on submissions
+    // submission_votes: Vec<SubmissionVote>, // Added to store votes on submissions
+    reviewer_applications: Vec<ReviewerApplication>,
+    required_endorsements: u32, // Endorsements (or rejections) needed to settle an application
+    keyword_index: HashMap<String, Vec<String>>, // Maps each keyword to the reviewers listing it
+    commit_window: u64,         // Time an assigned reviewer has to commit a vote, in nanoseconds
+    // Contract-wide rule used to finalize submissions outside of a track with its own rule
+    decision_rule: DecisionRule,
+    tracks: HashMap<String, Track>,
+    // Criteria scored from MIN_SCORE to MAX_SCORE on new submissions; empty disables scoring
+    scoring_rubric: Vec<String>,
+    // Time the author has to respond to comments once a rebuttal is opened, in nanoseconds
+    rebuttal_window: u64,
+    // Time reviewers have to reveal their votes once voting ends, in nanoseconds
+    reveal_window: u64,
+    // Fee attached to every submission and escrowed for its reviewers, in yoctoNEAR
+    review_fee: Balance,
+    // Whether accounts pay for the storage they use from their NEP-145 storage balance
+    charge_storage: bool,
+    // Stake a reviewer needs to be assigned to submissions; zero disables the requirement
+    min_stake: Balance,
+    // Time unstaked funds stay slashable before they can be withdrawn, in nanoseconds
+    unbonding_period: u64,
+    // Share of a reviewer's stake removed for a no-show, forfeited vote or upheld report
+    slash_percent: u8,
+    slashed_stake: Balance, // Slashed stake and forfeited deposits kept by the contract
+    misconduct_reports: Vec<MisconductReport>,
+    reward_formula: RewardFormula,
+    // NEP-145 storage balances deposited by each account
+    storage_deposits: HashMap<String, Balance>,
+    // Bytes of storage charged to each account
+    storage_used: HashMap<String, u64>,
+    bounty_pools: Vec<BountyPool>,
+    // Maps whitelisted NEP-141 tokens to the review fee charged in that token
+    accepted_tokens: HashMap<String, Balance>,
+    // Refundable deposit attached to NEAR submissions on top of the review fee
+    submission_deposit: Balance,
+    // Contract-wide policy used for submissions outside of a track with its own policy
+    refund_policy: RefundPolicy,
+    // Time editors and authors have to change a finalized outcome before deposits settle, in nanoseconds
+    editor_window: u64,
+    ledger: Vec<LedgerEntry>, // Every movement of funds, oldest first
+    // Time reviewers have to commit comments on a new submission or revision, in nanoseconds
+    comment_commit_window: u64,
+    // Time reviewers have to reveal comments once the comment commit window closes, in nanoseconds
+    comment_reveal_window: u64,
+    // Maps accounts to their registered X25519 public key, in hex
+    public_keys: HashMap<String, String>,
 }
 
 // Define the default, which automatically initializes the contract
@@ -84,6 +719,34 @@ impl Contract {
             authors: Vec::new(),
             reviewers: Vec::new(),
             submissions: Vec::new(), // Initialize submissions vector
+            reviewer_applications: Vec::new(),
+            required_endorsements: DEFAULT_REQUIRED_ENDORSEMENTS,
+            keyword_index: HashMap::new(),
+            commit_window: DEFAULT_COMMIT_WINDOW,
+            decision_rule: DecisionRule::default(),
+            tracks: HashMap::new(),
+            scoring_rubric: Vec::new(),
+            rebuttal_window: DEFAULT_REBUTTAL_WINDOW,
+            reveal_window: DEFAULT_REVEAL_WINDOW,
+            review_fee: 0,
+            charge_storage: false,
+            min_stake: 0,
+            unbonding_period: DEFAULT_UNBONDING_PERIOD,
+            slash_percent: DEFAULT_SLASH_PERCENT,
+            slashed_stake: 0,
+            misconduct_reports: Vec::new(),
+            reward_formula: RewardFormula::EqualSplit,
+            storage_deposits: HashMap::new(),
+            storage_used: HashMap::new(),
+            bounty_pools: Vec::new(),
+            accepted_tokens: HashMap::new(),
+            submission_deposit: 0,
+            refund_policy: RefundPolicy::default(),
+            editor_window: DEFAULT_EDITOR_WINDOW,
+            ledger: Vec::new(),
+            comment_commit_window: DEFAULT_COMMENT_COMMIT_WINDOW,
+            comment_reveal_window: DEFAULT_COMMENT_REVEAL_WINDOW,
+            public_keys: HashMap::new(),
         }
     }
 
@@ -106,7 +769,18 @@ impl Contract {
     // Updated to accept a reviewer name and keywords
     pub fn add_reviewer(&mut self, name: String, keywords: Vec<String>) {
         // if env::signer_account_id() == env::current_account_id() {
-        self.reviewers.push(Reviewer { name, keywords });
+        self.index_keywords(&name, &keywords);
+        self.reviewers.push(Reviewer {
+            name,
+            keywords,
+            no_shows: 0,
+            truthfulness_score: 0,
+            scored_reviews: 0,
+            unrevealed_commits: 0,
+            stake: 0,
+            unbonding: 0,
+            unbonding_until: 0,
+        });
         log_str("Reviewer added successfully.");
         // } else {
         // log_str("Only the contract owner can add reviewers.");
@@ -115,9 +789,12 @@ impl Contract {
 
     // Public method - allows a reviewer to add keywords to themselves
     pub fn add_keywords_to_reviewer(&mut self, name: String, new_keywords: Vec<String>) {
+        let initial_storage = env::storage_usage();
         if env::signer_account_id() == name.parse().unwrap() {
             if let Some(reviewer) = self.reviewers.iter_mut().find(|r| r.name == name) {
-                reviewer.keywords.extend(new_keywords);
+                reviewer.keywords.extend(new_keywords.clone());
+                self.index_keywords(&name, &new_keywords);
+                self.charge_storage_since(&name, initial_storage);
                 log_str("Keywords added successfully.");
             } else {
                 log_str("Reviewer not found.");
@@ -127,17 +804,144 @@ impl Contract {
         }
     }
 
+    // Public method - lets the owner configure how many endorsements approve an application
+    pub fn set_required_endorsements(&mut self, required_endorsements: u32) {
+        if env::signer_account_id() == env::current_account_id() {
+            if required_endorsements == 0 {
+                log_str("At least one endorsement must be required.");
+            } else {
+                self.required_endorsements = required_endorsements;
+                log_str("Required endorsements updated.");
+            }
+        } else {
+            log_str("Only the contract owner can change required endorsements.");
+        }
+    }
+
+    // Public method - lets any account apply to join the reviewer pool
+    // Returns the id of the new application
+    pub fn apply_as_reviewer(&mut self, keywords: Vec<String>, credentials: String) -> u64 {
+        let applicant = env::signer_account_id().to_string();
+        if self.reviewers.iter().any(|r| r.name == applicant) {
+            env::panic_str("Applicant is already a reviewer.");
+        }
+        if self
+            .reviewer_applications
+            .iter()
+            .any(|a| a.applicant == applicant && a.status == ApplicationStatus::Pending)
+        {
+            env::panic_str("Applicant already has a pending application.");
+        }
+        self.reviewer_applications.push(ReviewerApplication {
+            applicant,
+            keywords,
+            credentials,
+            endorsements: Vec::new(),
+            rejections: Vec::new(),
+            status: ApplicationStatus::Pending,
+        });
+        log_str("Reviewer application submitted.");
+        (self.reviewer_applications.len() - 1) as u64
+    }
+
+    // Public method - lets an existing reviewer endorse a pending application
+    // The applicant is added as a reviewer once enough endorsements are collected
+    pub fn endorse_application(&mut self, application_id: u64) {
+        if let Some(applicant) = self.record_application_review(application_id, true) {
+            let application = &self.reviewer_applications[application_id as usize];
+            if application.endorsements.len() as u32 >= self.required_endorsements {
+                let keywords = application.keywords.clone();
+                self.reviewer_applications[application_id as usize].status =
+                    ApplicationStatus::Approved;
+                // The applicant may have been added directly since applying
+                if self.reviewers.iter().any(|r| r.name == applicant) {
+                    log_str("Application approved. Applicant is already a reviewer.");
+                    return;
+                }
+                self.index_keywords(&applicant, &keywords);
+                self.reviewers.push(Reviewer {
+                    name: applicant,
+                    keywords,
+                    no_shows: 0,
+                    truthfulness_score: 0,
+                    scored_reviews: 0,
+                    unrevealed_commits: 0,
+                    stake: 0,
+                    unbonding: 0,
+                    unbonding_until: 0,
+                });
+                log_str("Application approved. Reviewer added successfully.");
+            }
+        }
+    }
+
+    // Public method - lets an existing reviewer reject a pending application
+    // The application is closed once enough rejections are collected
+    pub fn reject_application(&mut self, application_id: u64) {
+        if self
+            .record_application_review(application_id, false)
+            .is_some()
+        {
+            let application = &mut self.reviewer_applications[application_id as usize];
+            if application.rejections.len() as u32 >= self.required_endorsements {
+                application.status = ApplicationStatus::Rejected;
+                log_str("Application rejected.");
+            }
+        }
+    }
+
+    // Public method - returns all reviewer applications that are still pending
+    pub fn get_pending_applications(&self) -> Vec<(u64, ReviewerApplication)> {
+        self.reviewer_applications
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.status == ApplicationStatus::Pending)
+            .map(|(id, a)| (id as u64, a.clone()))
+            .collect()
+    }
+
+    // Records an endorsement or rejection by the signer and returns the applicant on success
+    fn record_application_review(&mut self, application_id: u64, endorse: bool) -> Option<String> {
+        let reviewer = env::signer_account_id().to_string();
+        let is_reviewer = self.reviewers.iter().any(|r| r.name == reviewer);
+        let application = match self.reviewer_applications.get_mut(application_id as usize) {
+            Some(application) => application,
+            None => env::panic_str("Application not found."),
+        };
+        if !is_reviewer {
+            log_str("Only reviewers can review applications.");
+        } else if application.status != ApplicationStatus::Pending {
+            log_str("Application is no longer pending.");
+        } else if application.applicant == reviewer {
+            log_str("Applicants cannot review their own application.");
+        } else if application.endorsements.contains(&reviewer)
+            || application.rejections.contains(&reviewer)
+        {
+            log_str("Reviewer has already reviewed this application.");
+        } else {
+            if endorse {
+                application.endorsements.push(reviewer);
+                log_str("Application endorsed.");
+            } else {
+                application.rejections.push(reviewer);
+                log_str("Application rejection recorded.");
+            }
+            return Some(application.applicant.clone());
+        }
+        None
+    }
+
     pub fn set_license(&mut self, license: String) {
         log_str(&format!("Saving license: {license}"));
         self.license = license;
     }
 
-    // Runs count_keywords_in_submission for each reviewer and returns the top 3 reviewers by count using a max-heap
+    // Counts matching keywords per reviewer through the keyword index and returns the top 3 reviewers by count using a max-heap
+    // Only reviewers with at least one keyword appearing in the submission are considered
     pub fn count_keywords_for_all_reviewers(&self, data: String) -> Vec<(String, u32)> {
         let mut heap = BinaryHeap::new();
-        for reviewer in &self.reviewers {
-            let count = self.count_keywords_in_submission(data.clone(), reviewer.keywords.clone());
-            heap.push(Reverse((count, reviewer.name.clone())));
+        for (name, count) in self.keyword_counts(&data) {
+            heap.push(Reverse((count, name.clone())));
             if heap.len() > 3 {
                 heap.pop();
             }
@@ -148,6 +952,47 @@ impl Contract {
             .collect()
     }
 
+    // Returns every reviewer with at least one matching keyword, ranked the same way as the max-heap above
+    fn rank_reviewers(&self, data: &str) -> Vec<(String, u32)> {
+        let mut ranked: Vec<(u32, String)> = self
+            .keyword_counts(data)
+            .into_iter()
+            .map(|(name, count)| (count, name.clone()))
+            .collect();
+        ranked.sort_by(|a, b| b.cmp(a));
+        ranked
+            .into_iter()
+            .map(|(count, name)| (name, count))
+            .collect()
+    }
+
+    // Counts matching keywords per reviewer, touching only keywords that appear in the data
+    fn keyword_counts(&self, data: &str) -> HashMap<&String, u32> {
+        let mut counts: HashMap<&String, u32> = HashMap::new();
+        for (keyword, names) in &self.keyword_index {
+            if data.contains(keyword.as_str()) {
+                for name in names {
+                    *counts.entry(name).or_insert(0) += 1;
+                }
+            }
+        }
+        // Stake is checked once per candidate rather than once per matched keyword
+        if self.min_stake > 0 {
+            counts.retain(|name, _| self.has_min_stake(name));
+        }
+        counts
+    }
+
+    // Adds a reviewer to the keyword index for each of the given keywords
+    fn index_keywords(&mut self, name: &str, keywords: &[String]) {
+        for keyword in keywords {
+            let names = self.keyword_index.entry(keyword.clone()).or_default();
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
     // Counts the number of keywords in a submission
     pub fn count_keywords_in_submission(&self, data: String, keywords: Vec<String>) -> u32 {
         keywords
@@ -157,40 +1002,464 @@ impl Contract {
     }
 
     // Public method - allows an author to submit data
+    // The attached deposit must cover the review fee and submission deposit, excess is refunded
+    // Storage is not paid from the attached deposit but charged to the author's NEP-145 storage balance
+    #[payable]
     pub fn submit_data(&mut self, data: String) {
         // if self.authors.contains(&env::signer_account_id().to_string()) {
-        let top_reviewers = self.count_keywords_for_all_reviewers(data.clone());
-        let suggested_reviewers: Vec<String> =
-            top_reviewers.into_iter().map(|(name, _)| name).collect();
-        self.submissions.push(Submission {
-            author: env::signer_account_id().to_string(),
-            response: data,
-            suggested_reviewers, // Record the suggested reviewers based on keyword count
-            submission_votes: SubmissionVote {
-                submission_id: 0, // Assuming a placeholder value; this should be updated according to your logic
-                vote_commits: Vec::new(),
-                revealed_votes: HashMap::new(),
-                comment_commits: Vec::new(),
-                revealed_comments: HashMap::new(),
-            },
-            voting_ended: false, // Explicitly initialize the voting_ended flag
-            accepted: None,      // Initialize the accepted field as None
-        });
-        log_str("Submission added successfully.");
+        let (review_fee, deposit) = self.collect_submission_fee();
+        self.create_submission(
+            env::signer_account_id().to_string(),
+            data,
+            None,
+            review_fee,
+            deposit,
+            None,
+        );
         // } else {
         //     log_str("Only authors can submit data.");
         // }
     }
 
-    // Function for reviewers to commit their vote on a submission
-    pub fn commit_vote(
-        &mut self,
-        submission_id: u64,
-        reviewer: String,
-        vote: String,
-        secret: String,
-    ) {
-        let combined = format!("{}{}", vote, secret);
+    // Public method - allows an author to submit data to a track
+    #[payable]
+    pub fn submit_to_track(&mut self, track: String, data: String) {
+        if !self.tracks.contains_key(&track) {
+            env::panic_str("Track not found.");
+        }
+        let (review_fee, deposit) = self.collect_submission_fee();
+        self.create_submission(
+            env::signer_account_id().to_string(),
+            data,
+            Some(track),
+            review_fee,
+            deposit,
+            None,
+        );
+    }
+
+    // Public method - lets the owner configure the refundable deposit attached to new submissions
+    pub fn set_submission_deposit(&mut self, deposit: U128) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.submission_deposit = deposit.0;
+            log_str("Submission deposit updated.");
+        } else {
+            log_str("Only the contract owner can change the submission deposit.");
+        }
+    }
+
+    // Public method - lets the owner configure the review fee attached to new submissions
+    // The fee only pays reviewers; charge_storage switches charging storage to NEP-145 storage balances
+    pub fn set_submission_fee(&mut self, review_fee: U128, charge_storage: bool) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.review_fee = review_fee.0;
+            self.charge_storage = charge_storage;
+            log_str("Submission fee updated.");
+        } else {
+            log_str("Only the contract owner can change the submission fee.");
+        }
+    }
+
+    // NEP-145 - registers an account or tops up its storage balance with the attached deposit
+    // With registration_only, anything above the minimum balance is refunded
+    #[payable]
+    pub fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let deposit = env::attached_deposit();
+        let registered = self.storage_deposits.contains_key(account_id.as_str());
+        if !registered && deposit < STORAGE_BALANCE_MIN {
+            env::panic_str("The attached deposit is less than the minimum storage balance.");
+        }
+        let kept = match (registration_only.unwrap_or(false), registered) {
+            (true, true) => 0,
+            (true, false) => STORAGE_BALANCE_MIN,
+            (false, _) => deposit,
+        };
+        *self
+            .storage_deposits
+            .entry(account_id.to_string())
+            .or_insert(0) += kept;
+        if kept > 0 {
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::StorageDeposited,
+                account_id.as_str(),
+                None,
+                kept,
+                None,
+            ));
+        }
+        if deposit > kept {
+            Promise::new(env::predecessor_account_id()).transfer(deposit - kept);
+        }
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    // NEP-145 - withdraws the given amount, or all available storage balance, to the caller
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self
+            .storage_balance_of(account_id.clone())
+            .unwrap_or_else(|| env::panic_str("The account is not registered."));
+        let amount = amount.unwrap_or(balance.available).0;
+        if amount > balance.available.0 {
+            env::panic_str("The amount is greater than the available storage balance.");
+        }
+        if amount > 0 {
+            *self.storage_deposits.get_mut(account_id.as_str()).unwrap() -= amount;
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::StorageWithdrawn,
+                account_id.as_str(),
+                None,
+                amount,
+                None,
+            ));
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    // NEP-145 - returns the storage balance of an account, if it is registered
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_deposits
+            .get(account_id.as_str())
+            .map(|total| StorageBalance {
+                total: U128(*total),
+                available: U128(total - self.storage_locked(account_id.as_str())),
+            })
+    }
+
+    // NEP-145 - returns the minimum storage balance for registration; there is no maximum
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(STORAGE_BALANCE_MIN),
+            max: None,
+        }
+    }
+
+    // Records a new submission and assigns its reviewer panel
+    fn create_submission(
+        &mut self,
+        author: String,
+        data: String,
+        track: Option<String>,
+        review_fee: Balance,
+        deposit: Balance,
+        fee_token: Option<String>,
+    ) {
+        let initial_storage = env::storage_usage();
+        let top_reviewers = self.count_keywords_for_all_reviewers(data.clone());
+        let suggested_reviewers: Vec<String> =
+            top_reviewers.into_iter().map(|(name, _)| name).collect();
+        let commit_deadline = env::block_timestamp() + self.commit_window;
+        let commit_deadlines = suggested_reviewers
+            .iter()
+            .map(|name| (name.clone(), commit_deadline))
+            .collect();
+        let submission_id = self.submissions.len() as u64;
+        let comment_commit_deadline = env::block_timestamp() + self.comment_commit_window;
+        let submission = Submission {
+            author,
+            response: data,
+            suggested_reviewers, // Record the suggested reviewers based on keyword count
+            submission_votes: SubmissionVote {
+                submission_id,
+                vote_commits: Vec::new(),
+                revealed_votes: HashMap::new(),
+                comment_commits: Vec::new(),
+                revealed_comments: Vec::new(),
+                revealed_scores: HashMap::new(),
+                revealed_confidences: HashMap::new(),
+                rebuttal_deadline: None,
+                rebuttals: HashMap::new(),
+                vote_update_commits: Vec::new(),
+                revealed_predictions: HashMap::new(),
+                truthfulness_scores: HashMap::new(),
+                forfeited_reviewers: Vec::new(),
+                public_comments: Vec::new(),
+                next_comment_id: 0,
+                encrypted_comments: Vec::new(),
+                update_reveal_deadline: None,
+                revealed_updates: Vec::new(),
+            },
+            voting_ended: false, // Explicitly initialize the voting_ended flag
+            reveal_deadline: None,
+            comment_commit_deadline,
+            comment_reveal_deadline: comment_commit_deadline + self.comment_reveal_window,
+            accepted: None, // Initialize the accepted field as None
+            commit_deadlines,
+            replaced_reviewers: Vec::new(),
+            track,
+            decision: None,
+            scoring_rubric: self.scoring_rubric.clone(),
+            score_aggregates: Vec::new(),
+            editor: None,
+            editor_decision: None,
+            round: 1,
+            revisions_requested: false,
+            history: Vec::new(),
+            appeal: None,
+            storage_cost: 0,
+            review_fee,
+            reward_payouts: HashMap::new(),
+            reward_refunded: 0,
+            fee_token,
+            deposit,
+            deposit_refunded: 0,
+            deposit_forfeited: 0,
+            desk_rejection: None,
+            fees_settled: false,
+            editor_deadline: None,
+            deposit_settled: false,
+            annotations: Vec::new(),
+        };
+        for (kind, amount) in [
+            (LedgerKind::FeeCollected, review_fee),
+            (LedgerKind::DepositCollected, deposit),
+        ] {
+            if amount > 0 {
+                self.ledger.push(LedgerEntry::new(
+                    kind,
+                    &submission.author,
+                    Some(submission_id),
+                    amount,
+                    submission.fee_token.as_ref(),
+                ));
+            }
+        }
+        let author = submission.author.clone();
+        self.submissions.push(submission);
+        let bytes = self.charge_storage_since(&author, initial_storage);
+        self.submissions[submission_id as usize].storage_cost =
+            bytes as u128 * env::storage_byte_cost();
+        log_str("Submission added successfully.");
+    }
+
+    // Public method - lets the owner set the scoring rubric used for new submissions
+    pub fn set_scoring_rubric(&mut self, criteria: Vec<String>) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.scoring_rubric = criteria;
+            log_str("Scoring rubric updated.");
+        } else {
+            log_str("Only the contract owner can change the scoring rubric.");
+        }
+    }
+
+    // Public method - returns the per-criterion score aggregates of a finalized submission
+    pub fn get_score_aggregates(&self, submission_id: u64) -> Vec<CriterionAggregate> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .score_aggregates
+            .clone()
+    }
+
+    // Splits a review fee among the reviewers who revealed, following the reward formula
+    // Rounding leftovers stay with the author and are not part of the returned shares
+    fn reward_shares(
+        formula: &RewardFormula,
+        panel: &[String],
+        revealed_votes: &HashMap<String, String>,
+        outcome: &DecisionOutcome,
+        fee: Balance,
+    ) -> Vec<(String, Balance)> {
+        let mut weights: Vec<(String, u128)> = revealed_votes
+            .iter()
+            .filter(|(reviewer, _)| panel.contains(reviewer))
+            .map(|(reviewer, vote)| {
+                let aligned = matches!(
+                    (vote.as_str(), outcome),
+                    ("accept", DecisionOutcome::Accepted) | ("reject", DecisionOutcome::Rejected)
+                );
+                let weight = match formula {
+                    RewardFormula::OutcomeAlignment if aligned => 2,
+                    _ => 1,
+                };
+                (reviewer.clone(), weight)
+            })
+            .collect();
+        weights.sort();
+        let total: u128 = weights.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        weights
+            .into_iter()
+            .map(|(reviewer, weight)| (reviewer, fee * weight / total))
+            .filter(|(_, share)| *share > 0)
+            .collect()
+    }
+
+    // Computes mean, median and spread for each rubric criterion over the revealed scores
+    fn aggregate_scores(
+        rubric: &[String],
+        revealed_scores: &HashMap<String, Vec<u8>>,
+    ) -> Vec<CriterionAggregate> {
+        if revealed_scores.is_empty() {
+            return Vec::new();
+        }
+        rubric
+            .iter()
+            .enumerate()
+            .map(|(i, criterion)| {
+                let mut scores: Vec<u32> = revealed_scores
+                    .values()
+                    .map(|scores| scores[i] as u32)
+                    .collect();
+                scores.sort_unstable();
+                let count = scores.len() as u32;
+                // Averaging the two middle entries also covers an odd count, where they coincide
+                let median = (scores[(scores.len() - 1) / 2] + scores[scores.len() / 2]) * 50;
+                CriterionAggregate {
+                    criterion: criterion.clone(),
+                    mean: scores.iter().sum::<u32>() * 100 / count,
+                    median,
+                    spread: (scores[scores.len() - 1] - scores[0]) as u8,
+                }
+            })
+            .collect()
+    }
+
+    // Computes a Bayesian Truth Serum score per reviewer from their revealed vote and prediction
+    // Scores reward answers that are more common than collectively predicted, plus accurate predictions
+    fn truthfulness_scores(
+        revealed_votes: &HashMap<String, String>,
+        revealed_predictions: &HashMap<String, u8>,
+    ) -> HashMap<String, i64> {
+        let participants: Vec<(&String, bool, f64)> = revealed_predictions
+            .iter()
+            .filter_map(|(reviewer, prediction)| {
+                revealed_votes.get(reviewer).map(|vote| {
+                    // Clamp so that the logarithms below stay finite
+                    let p = (*prediction as f64 / 100.0).clamp(0.01, 0.99);
+                    (reviewer, vote == "accept", p)
+                })
+            })
+            .collect();
+        if participants.len() < 2 {
+            return HashMap::new();
+        }
+        let n = participants.len() as f64;
+        let accept_share = participants.iter().filter(|(_, accept, _)| *accept).count() as f64 / n;
+        let reject_share = 1.0 - accept_share;
+        // Logarithms of the geometric means of the predicted accept and reject shares
+        let log_mean_accept = participants.iter().map(|(_, _, p)| p.ln()).sum::<f64>() / n;
+        let log_mean_reject = participants
+            .iter()
+            .map(|(_, _, p)| (1.0 - p).ln())
+            .sum::<f64>()
+            / n;
+        participants
+            .iter()
+            .map(|(reviewer, accept, p)| {
+                let information = if *accept {
+                    accept_share.ln() - log_mean_accept
+                } else {
+                    reject_share.ln() - log_mean_reject
+                };
+                let mut prediction = 0.0;
+                if accept_share > 0.0 {
+                    prediction += accept_share * (p.ln() - log_mean_accept);
+                }
+                if reject_share > 0.0 {
+                    prediction += reject_share * ((1.0 - p).ln() - log_mean_reject);
+                }
+                let score = ((information + prediction) * TRUTHFULNESS_SCALE).round() as i64;
+                ((*reviewer).clone(), score)
+            })
+            .collect()
+    }
+
+    // Public method - lets the owner create a track
+    pub fn add_track(&mut self, name: String) {
+        if env::signer_account_id() == env::current_account_id() {
+            if let Entry::Vacant(entry) = self.tracks.entry(name) {
+                entry.insert(Track::default());
+                log_str("Track added successfully.");
+            } else {
+                log_str("Track already exists.");
+            }
+        } else {
+            log_str("Only the contract owner can add tracks.");
+        }
+    }
+
+    // Public method - lets the owner set the contract-wide decision rule
+    pub fn set_decision_rule(&mut self, rule: DecisionRule) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change the decision rule.");
+        } else if !rule.is_valid() {
+            log_str("Invalid decision rule.");
+        } else {
+            self.decision_rule = rule;
+            log_str("Decision rule updated.");
+        }
+    }
+
+    // Public method - lets the owner set or clear a track's decision rule
+    pub fn set_track_decision_rule(&mut self, track: String, rule: Option<DecisionRule>) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change the decision rule.");
+        } else if rule.as_ref().is_some_and(|rule| !rule.is_valid()) {
+            log_str("Invalid decision rule.");
+        } else if let Some(config) = self.tracks.get_mut(&track) {
+            config.decision_rule = rule;
+            log_str("Track decision rule updated.");
+        } else {
+            env::panic_str("Track not found.");
+        }
+    }
+
+    // Public method - returns the decision recorded for a submission, if any
+    pub fn get_decision(&self, submission_id: u64) -> Option<Decision> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .decision
+            .clone()
+    }
+
+    // Returns the decision rule that applies to a submission
+    fn decision_rule_for(&self, submission: &Submission) -> DecisionRule {
+        submission
+            .track
+            .as_ref()
+            .and_then(|track| self.tracks.get(track))
+            .and_then(|track| track.decision_rule.clone())
+            .unwrap_or_else(|| self.decision_rule.clone())
+    }
+
+    // Function for reviewers to commit their vote on a submission
+    pub fn commit_vote(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        vote: String,
+        secret: String,
+    ) {
+        let ballot = Ballot {
+            vote,
+            ..Default::default()
+        };
+        self.commit_ballot(submission_id, reviewer, ballot, secret);
+    }
+
+    // Function for reviewers to commit their vote together with rubric scores under one commitment
+    pub fn commit_ballot(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        ballot: Ballot,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", ballot.preimage(), secret);
         let hash = Sha256::digest(combined.as_bytes());
         let commit = format!("{:x}", hash);
 
@@ -199,7 +1468,13 @@ impl Contract {
             .iter_mut()
             .find(|sub| sub.submission_votes.submission_id == submission_id)
         {
-            if submission_vote
+            if env::signer_account_id().as_str() != reviewer {
+                log_str("Only the reviewer can commit their own vote.");
+            } else if submission_vote.replaced_reviewers.contains(&reviewer) {
+                log_str("Reviewer was replaced on this submission. Vote not committed.");
+            } else if !submission_vote.suggested_reviewers.contains(&reviewer) {
+                log_str("Reviewer is not assigned to this submission. Vote not committed.");
+            } else if submission_vote
                 .submission_votes
                 .vote_commits
                 .iter()
@@ -218,16 +1493,112 @@ impl Contract {
         }
     }
 
-    // Function to end voting on a submission
+    // Public method - replaces an assigned reviewer whose commit window passed without a vote commit
+    // Anyone can call this; the next-ranked eligible candidate joins the panel with a fresh deadline
+    pub fn replace_stale_reviewer(&mut self, submission_id: u64, reviewer: String) {
+        let index = self
+            .submissions
+            .iter()
+            .position(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let submission = &self.submissions[index];
+        if submission.voting_ended {
+            log_str("Voting has already ended.");
+            return;
+        }
+        if !submission.suggested_reviewers.contains(&reviewer) {
+            log_str("Reviewer is not assigned to this submission.");
+            return;
+        }
+        if submission
+            .submission_votes
+            .vote_commits
+            .iter()
+            .any(|vc| vc.reviewer == reviewer)
+        {
+            log_str("Reviewer has already committed a vote.");
+            return;
+        }
+        let deadline = submission
+            .commit_deadlines
+            .get(&reviewer)
+            .copied()
+            .unwrap_or(0);
+        if env::block_timestamp() <= deadline {
+            log_str("Commit window has not passed yet.");
+            return;
+        }
+
+        let replacement = self
+            .rank_reviewers(&submission.response)
+            .into_iter()
+            .map(|(name, _)| name)
+            .find(|name| {
+                *name != reviewer
+                    && *name != submission.author
+                    && !submission.suggested_reviewers.contains(name)
+                    && !submission.replaced_reviewers.contains(name)
+            });
+        let new_deadline = env::block_timestamp() + self.commit_window;
+
+        let submission = &mut self.submissions[index];
+        submission
+            .suggested_reviewers
+            .retain(|name| *name != reviewer);
+        submission.commit_deadlines.remove(&reviewer);
+        submission.replaced_reviewers.push(reviewer.clone());
+        if let Some(replacement) = replacement {
+            submission
+                .commit_deadlines
+                .insert(replacement.clone(), new_deadline);
+            submission.suggested_reviewers.push(replacement);
+            log_str("Stale reviewer replaced successfully.");
+        } else {
+            log_str("Stale reviewer removed. No eligible replacement reviewer available.");
+        }
+
+        if let Some(profile) = self.reviewers.iter_mut().find(|r| r.name == reviewer) {
+            profile.no_shows += 1;
+        }
+        self.slash(&reviewer);
+    }
+
+    // Public method - lets the owner configure the commit window for assigned reviewers
+    pub fn set_commit_window(&mut self, commit_window: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.commit_window = commit_window;
+            log_str("Commit window updated.");
+        } else {
+            log_str("Only the contract owner can change the commit window.");
+        }
+    }
+
+    // Function to end voting on a submission once the whole panel has committed or timed out
     pub fn end_voting(&mut self, submission_id: u64) {
+        let reveal_deadline = env::block_timestamp() + self.reveal_window;
         let submission_vote = self
             .submissions
             .iter_mut()
             .find(|sub| sub.submission_votes.submission_id == submission_id);
 
         if let Some(submission_vote) = submission_vote {
-            if submission_vote.submission_votes.vote_commits.len() == 3 {
+            // Every panel member must have committed or let their commit deadline pass
+            let panel_done = submission_vote.suggested_reviewers.iter().all(|name| {
+                submission_vote
+                    .submission_votes
+                    .vote_commits
+                    .iter()
+                    .any(|vc| vc.reviewer == *name)
+                    || env::block_timestamp()
+                        > submission_vote
+                            .commit_deadlines
+                            .get(name)
+                            .copied()
+                            .unwrap_or(0)
+            });
+            if panel_done {
                 submission_vote.voting_ended = true; // Mark voting as ended
+                submission_vote.reveal_deadline = Some(reveal_deadline);
                 log_str("Voting ended successfully.");
             } else {
                 log_str("Not all reviewers have committed their votes.");
@@ -237,6 +1608,52 @@ impl Contract {
         }
     }
 
+    // Public method - records committed votes that were not revealed before the reveal deadline
+    // Anyone can call this; each forfeit is counted on the reviewer's profile
+    pub fn forfeit_unrevealed_votes(&mut self, submission_id: u64) {
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if !submission.reveal_closed() {
+            log_str("Reveal window has not passed yet.");
+            return;
+        }
+        let votes = &mut submission.submission_votes;
+        let forfeited: Vec<String> = votes
+            .vote_commits
+            .iter()
+            .map(|vc| vc.reviewer.clone())
+            .filter(|name| {
+                !votes.revealed_votes.contains_key(name)
+                    && !votes.forfeited_reviewers.contains(name)
+            })
+            .collect();
+        if forfeited.is_empty() {
+            log_str("No unrevealed votes to forfeit.");
+            return;
+        }
+        votes.forfeited_reviewers.extend(forfeited.iter().cloned());
+        for name in &forfeited {
+            if let Some(profile) = self.reviewers.iter_mut().find(|r| r.name == *name) {
+                profile.unrevealed_commits += 1;
+            }
+            self.slash(name);
+        }
+        log_str("Unrevealed votes forfeited.");
+    }
+
+    // Public method - lets the owner configure how long reviewers have to reveal once voting ends
+    pub fn set_reveal_window(&mut self, reveal_window: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.reveal_window = reveal_window;
+            log_str("Reveal window updated.");
+        } else {
+            log_str("Only the contract owner can change the reveal window.");
+        }
+    }
+
     // Function for reviewers to reveal their vote on a submission
     pub fn reveal_vote(
         &mut self,
@@ -245,7 +1662,22 @@ impl Contract {
         vote: String,
         secret: String,
     ) {
-        let combined = format!("{}{}", vote, secret);
+        let ballot = Ballot {
+            vote,
+            ..Default::default()
+        };
+        self.reveal_ballot(submission_id, reviewer, ballot, secret);
+    }
+
+    // Function for reviewers to reveal their vote and rubric scores on a submission
+    pub fn reveal_ballot(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        ballot: Ballot,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", ballot.preimage(), secret);
         let hash = Sha256::digest(combined.as_bytes());
         let commit = format!("{:x}", hash);
 
@@ -254,7 +1686,28 @@ impl Contract {
             .iter_mut()
             .find(|sub| sub.submission_votes.submission_id == submission_id)
         {
-            if submission.voting_ended {
+            let scores_valid = ballot.scores.len() == submission.scoring_rubric.len()
+                && ballot
+                    .scores
+                    .iter()
+                    .all(|score| (MIN_SCORE..=MAX_SCORE).contains(score));
+            let confidence_valid = ballot
+                .confidence
+                .is_none_or(|c| (MIN_CONFIDENCE..=MAX_CONFIDENCE).contains(&c));
+            let prediction_valid = ballot.prediction.is_none_or(|p| p <= 100);
+            if ballot.vote != "accept" && ballot.vote != "reject" {
+                log_str("Vote reveal failed: Vote must be \"accept\" or \"reject\".");
+            } else if !scores_valid {
+                log_str("Vote reveal failed: Scores do not match the scoring rubric.");
+            } else if !confidence_valid {
+                log_str("Vote reveal failed: Confidence is out of range.");
+            } else if !prediction_valid {
+                log_str("Vote reveal failed: Prediction must be a percentage.");
+            } else if submission.decision.is_some() {
+                log_str("Vote reveal failed: Submission has already been finalized.");
+            } else if submission.reveal_closed() {
+                log_str("Vote reveal failed: Reveal window has closed.");
+            } else if submission.voting_ended {
                 if let Some(vote_commit) = submission
                     .submission_votes
                     .vote_commits
@@ -262,10 +1715,28 @@ impl Contract {
                     .find(|vc| vc.reviewer == reviewer)
                 {
                     if vote_commit.commit == commit {
+                        if !ballot.scores.is_empty() {
+                            submission
+                                .submission_votes
+                                .revealed_scores
+                                .insert(reviewer.clone(), ballot.scores);
+                        }
+                        if let Some(confidence) = ballot.confidence {
+                            submission
+                                .submission_votes
+                                .revealed_confidences
+                                .insert(reviewer.clone(), confidence);
+                        }
+                        if let Some(prediction) = ballot.prediction {
+                            submission
+                                .submission_votes
+                                .revealed_predictions
+                                .insert(reviewer.clone(), prediction);
+                        }
                         submission
                             .submission_votes
                             .revealed_votes
-                            .insert(reviewer, vote);
+                            .insert(reviewer, ballot.vote);
                         log_str("Vote revealed successfully.");
                     } else {
                         log_str("Vote reveal failed: Commit does not match.");
@@ -281,14 +1752,38 @@ impl Contract {
         }
     }
 
-    // Function for reviewers to commit their comment on a submission
+    // Function for reviewers to commit their comment on a submission, returning the comment ID
     pub fn commit_comment(
         &mut self,
         submission_id: u64,
         reviewer: String,
         comment: String,
         secret: String,
-    ) {
+    ) -> u64 {
+        self.commit_comment_in_thread(submission_id, reviewer, None, comment, secret)
+    }
+
+    // Function for reviewers to commit a reply to an earlier comment, returning the reply's ID
+    pub fn commit_reply(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        parent_id: u64,
+        comment: String,
+        secret: String,
+    ) -> u64 {
+        self.commit_comment_in_thread(submission_id, reviewer, Some(parent_id), comment, secret)
+    }
+
+    // Records a comment commitment under the next comment ID of the submission
+    fn commit_comment_in_thread(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        parent_id: Option<u64>,
+        comment: String,
+        secret: String,
+    ) -> u64 {
         let combined = format!("{}{}", comment, secret);
         let hash = Sha256::digest(combined.as_bytes());
         let commit = format!("{:x}", hash);
@@ -298,15 +1793,34 @@ impl Contract {
             .iter_mut()
             .find(|sub| sub.submission_votes.submission_id == submission_id)
         {
-            submission_vote
-                .submission_votes
-                .comment_commits
-                .push(CommentCommit { reviewer, commit });
+            if env::signer_account_id().as_str() != reviewer {
+                env::panic_str("Only the reviewer can commit their own comment.");
+            }
+            if !submission_vote.suggested_reviewers.contains(&reviewer) {
+                env::panic_str("Only assigned reviewers can comment on a submission.");
+            }
+            if env::block_timestamp() > submission_vote.comment_commit_deadline {
+                env::panic_str("Comment commit window has closed.");
+            }
+            let votes = &mut submission_vote.submission_votes;
+            let comment_id = votes.next_comment_id;
+            if parent_id.is_some_and(|parent_id| parent_id >= comment_id) {
+                env::panic_str("Parent comment not found.");
+            }
+            votes.next_comment_id += 1;
+            votes.comment_commits.push(CommentCommit {
+                comment_id,
+                reviewer,
+                commit,
+                parent_id,
+            });
             log_str("Comment committed successfully.");
+            comment_id
         } else {
             env::panic_str("Submission not found.");
         }
     }
+
     // Function to return the data of submissions that have been accepted
     pub fn get_accepted_submissions(&self) -> Vec<String> {
         self.submissions
@@ -324,6 +1838,7 @@ impl Contract {
         comment: String,
         secret: String,
     ) {
+        let initial_storage = env::storage_usage();
         let combined = format!("{}{}", comment, secret);
         let hash = Sha256::digest(combined.as_bytes());
         let commit = format!("{:x}", hash);
@@ -333,526 +1848,4366 @@ impl Contract {
             .iter_mut()
             .find(|sub| sub.submission_votes.submission_id == submission_id)
         {
-            if submission.voting_ended {
-                if let Some(comment_commit) = submission
-                    .submission_votes
+            if env::block_timestamp() <= submission.comment_commit_deadline {
+                log_str("Comment commit window is still open.");
+            } else if env::block_timestamp() > submission.comment_reveal_deadline {
+                log_str("Comment reveal window has closed.");
+            } else {
+                let votes = &mut submission.submission_votes;
+                let mut unrevealed = votes
                     .comment_commits
                     .iter()
-                    .find(|cc| cc.reviewer == reviewer)
-                {
-                    if comment_commit.commit == commit {
-                        submission
-                            .submission_votes
+                    .filter(|cc| cc.reviewer == reviewer)
+                    .filter(|cc| {
+                        !votes
                             .revealed_comments
-                            .insert(reviewer, comment);
-                        log_str("Comment revealed successfully.");
-                    } else {
-                        log_str("Comment reveal failed: Commit does not match.");
-                    }
-                } else {
+                            .iter()
+                            .any(|c| c.comment_id == cc.comment_id)
+                    })
+                    .peekable();
+                if unrevealed.peek().is_none() {
                     log_str("Comment commit not found for reviewer.");
+                } else if let Some(comment_commit) = unrevealed.find(|cc| cc.commit == commit) {
+                    votes.revealed_comments.push(Comment {
+                        comment_id: comment_commit.comment_id,
+                        commenter: reviewer.clone(),
+                        text: comment,
+                        parent_id: comment_commit.parent_id,
+                    });
+                    self.charge_storage_since(&reviewer, initial_storage);
+                    log_str("Comment revealed successfully.");
+                } else {
+                    log_str("Comment reveal failed: Commit does not match.");
                 }
-            } else {
-                log_str("Voting has not ended yet.");
             }
         } else {
             env::panic_str("Submission not found.");
         }
     }
 
-    // Function to finalize the submission after all votes are revealed
-    // This function checks if all reviewers voted to accept and sets the submission's accepted flag accordingly
-    pub fn finalize_submission(&mut self, submission_id: u64) {
+    // Public method - returns the revealed comments of a submission's current round
+    pub fn get_comments(&self, submission_id: u64) -> Vec<Comment> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .submission_votes
+            .revealed_comments
+            .clone()
+    }
+
+    // Public method - lets anyone post an uncommitted public comment once a decision exists
+    // Returns the comment ID; replies share the ID space of reviewer comments
+    pub fn post_public_comment(
+        &mut self,
+        submission_id: u64,
+        text: String,
+        parent_id: Option<u64>,
+    ) -> u64 {
+        let initial_storage = env::storage_usage();
+        let commenter = env::signer_account_id().to_string();
         let submission = self
             .submissions
             .iter_mut()
-            .find(|sub| sub.submission_votes.submission_id == submission_id);
-        if let Some(submission) = submission {
-            if submission.voting_ended {
-                let all_accepted = submission
-                    .submission_votes
-                    .revealed_votes
-                    .values()
-                    .all(|vote| vote == "accept");
-                submission.accepted = Some(all_accepted);
-                if all_accepted {
-                    log_str("Submission accepted.");
-                } else {
-                    log_str("Submission rejected.");
-                }
-            } else {
-                log_str("Voting has not ended yet.");
-            }
-        } else {
-            env::panic_str("Submission not found.");
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if submission.decision.is_none() && submission.desk_rejection.is_none() {
+            env::panic_str("Public comments open once a decision exists.");
+        }
+        if text.trim().is_empty() {
+            env::panic_str("A comment cannot be empty.");
         }
+        let votes = &mut submission.submission_votes;
+        let comment_id = votes.next_comment_id;
+        if parent_id.is_some_and(|parent_id| parent_id >= comment_id) {
+            env::panic_str("Parent comment not found.");
+        }
+        votes.next_comment_id += 1;
+        votes.public_comments.push(Comment {
+            comment_id,
+            commenter: commenter.clone(),
+            text,
+            parent_id,
+        });
+        self.charge_storage_since(&commenter, initial_storage);
+        log_str("Public comment posted successfully.");
+        comment_id
     }
-}
 
-/*
- * The rest of this file holds the inline tests for the code above
- * Learn more about Rust tests: https://doc.rust-lang.org/book/ch11-01-writing-tests.html
- */
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::test_utils::{accounts, VMContextBuilder};
-    use near_sdk::{testing_env, VMContext};
+    // Public method - returns the public comments of a submission's current round
+    pub fn get_public_comments(&self, submission_id: u64) -> Vec<Comment> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .submission_votes
+            .public_comments
+            .clone()
+    }
 
-    fn get_context(is_owner: bool) -> VMContext {
-        let mut builder = VMContextBuilder::new();
-        if is_owner {
-            builder
-                .current_account_id(accounts(0))
-                .signer_account_id(accounts(0));
+    // Public method - lets the owner configure the comment commit and reveal windows
+    pub fn set_comment_windows(&mut self, commit_window: u64, reveal_window: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.comment_commit_window = commit_window;
+            self.comment_reveal_window = reveal_window;
+            log_str("Comment windows updated.");
         } else {
-            builder
-                .current_account_id(accounts(0))
-                .signer_account_id(accounts(1));
+            log_str("Only the contract owner can change the comment windows.");
         }
-        builder.build()
     }
 
-    #[test]
-    fn add_author_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "author.testnet".parse().unwrap();
-        testing_env!(context);
-        let mut contract = Contract::new();
-        contract.add_author("snotty-body.testnet".to_string());
-        assert_eq!(contract.authors.len(), 1);
-        assert_eq!(contract.authors[0], "snotty-body.testnet");
+    // Public method - registers the signer's X25519 public key, given as 32 bytes in hex
+    pub fn register_public_key(&mut self, public_key: String) {
+        if !Self::is_hex(&public_key, Some(X25519_KEY_LENGTH)) {
+            log_str("Public key must be 32 bytes in hex.");
+            return;
+        }
+        let initial_storage = env::storage_usage();
+        let account = env::signer_account_id().to_string();
+        self.public_keys
+            .insert(account.clone(), public_key.to_lowercase());
+        self.charge_storage_since(&account, initial_storage);
+        log_str("Public key registered.");
     }
 
-    #[test]
-    fn add_reviewer_success() {
-        let context = get_context(true);
-        testing_env!(context);
+    // Public method - returns an account's registered X25519 public key, if any
+    pub fn get_public_key(&self, account_id: String) -> Option<String> {
+        self.public_keys.get(&account_id).cloned()
+    }
+
+    // Public method - lets an assigned reviewer attach a comment encrypted to the author's public key
+    // Ciphertext and nonce are hex; the contract only checks their shape and keeps them auditable
+    pub fn post_encrypted_comment(
+        &mut self,
+        submission_id: u64,
+        ciphertext: String,
+        nonce: String,
+    ) -> u64 {
+        let initial_storage = env::storage_usage();
+        let reviewer = env::signer_account_id().to_string();
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if !submission.suggested_reviewers.contains(&reviewer) {
+            env::panic_str("Only assigned reviewers can post encrypted comments.");
+        }
+        let author_key = self
+            .public_keys
+            .get(&submission.author)
+            .cloned()
+            .unwrap_or_else(|| env::panic_str("The author has not registered a public key."));
+        if ciphertext.is_empty() || !Self::is_hex(&ciphertext, None) {
+            env::panic_str("Ciphertext must be hex.");
+        }
+        if !Self::is_hex(&nonce, Some(NONCE_LENGTH)) {
+            env::panic_str("Nonce must be 24 bytes in hex.");
+        }
+        let votes = &mut submission.submission_votes;
+        let comment_id = votes.next_comment_id;
+        votes.next_comment_id += 1;
+        votes.encrypted_comments.push(EncryptedComment {
+            comment_id,
+            reviewer: reviewer.clone(),
+            author_key,
+            ciphertext,
+            nonce,
+        });
+        self.charge_storage_since(&reviewer, initial_storage);
+        log_str("Encrypted comment posted successfully.");
+        comment_id
+    }
+
+    // Public method - returns the encrypted comments of a submission's current round
+    pub fn get_encrypted_comments(&self, submission_id: u64) -> Vec<EncryptedComment> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .submission_votes
+            .encrypted_comments
+            .clone()
+    }
+
+    // Public method - lets an assigned reviewer anchor a comment to a character range of the text
+    // The range is [start, end) in characters of the given round's version, the current one by default
+    pub fn annotate(
+        &mut self,
+        submission_id: u64,
+        round: Option<u32>,
+        start: u32,
+        end: u32,
+        text: String,
+    ) -> u64 {
+        let initial_storage = env::storage_usage();
+        let reviewer = env::signer_account_id().to_string();
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if !submission.suggested_reviewers.contains(&reviewer) {
+            env::panic_str("Only assigned reviewers can annotate a submission.");
+        }
+        let round = round.unwrap_or(submission.round);
+        let response = if round == submission.round {
+            &submission.response
+        } else {
+            &submission
+                .history
+                .iter()
+                .find(|past| past.round == round)
+                .unwrap_or_else(|| env::panic_str("Review round not found."))
+                .response
+        };
+        if start >= end || end as usize > response.chars().count() {
+            env::panic_str("Annotation range is out of bounds.");
+        }
+        let annotation_id = submission.annotations.len() as u64;
+        submission.annotations.push(Annotation {
+            annotation_id,
+            reviewer: reviewer.clone(),
+            round,
+            start,
+            end,
+            text,
+        });
+        self.charge_storage_since(&reviewer, initial_storage);
+        log_str("Annotation added successfully.");
+        annotation_id
+    }
+
+    // Public method - returns the annotations of a round, the current one by default, ordered by position
+    pub fn get_annotations(&self, submission_id: u64, round: Option<u32>) -> Vec<Annotation> {
+        let submission = self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let round = round.unwrap_or(submission.round);
+        let mut annotations: Vec<Annotation> = submission
+            .annotations
+            .iter()
+            .filter(|annotation| annotation.round == round)
+            .cloned()
+            .collect();
+        annotations.sort_by_key(|annotation| (annotation.start, annotation.end));
+        annotations
+    }
+
+    // Public method - lets the author open the rebuttal window once reviewer comments are revealed
+    pub fn open_rebuttal(&mut self, submission_id: u64) {
+        let rebuttal_deadline = env::block_timestamp() + self.rebuttal_window;
+        let update_reveal_deadline = rebuttal_deadline + self.reveal_window;
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if submission.author != env::signer_account_id().to_string() {
+            log_str("Only the author can open a rebuttal.");
+        } else if submission.decision.is_some() {
+            log_str("Submission has already been finalized.");
+        } else if submission.submission_votes.revealed_comments.is_empty() {
+            log_str("No comments have been revealed yet.");
+        } else if submission.submission_votes.rebuttal_deadline.is_some() {
+            log_str("Rebuttal has already been opened.");
+        } else {
+            submission.submission_votes.rebuttal_deadline = Some(rebuttal_deadline);
+            submission.submission_votes.update_reveal_deadline = Some(update_reveal_deadline);
+            log_str("Rebuttal window opened.");
+        }
+    }
+
+    // Public method - lets the author respond once to a revealed comment
+    pub fn post_rebuttal(&mut self, submission_id: u64, comment_id: u64, response: String) {
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let votes = &mut submission.submission_votes;
+        if submission.author != env::signer_account_id().to_string() {
+            log_str("Only the author can post a rebuttal.");
+        } else if !votes.rebuttal_open() {
+            log_str("Rebuttal window is not open.");
+        } else if !votes
+            .revealed_comments
+            .iter()
+            .any(|c| c.comment_id == comment_id)
+        {
+            log_str("No revealed comment found with this ID.");
+        } else if let Entry::Vacant(entry) = votes.rebuttals.entry(comment_id) {
+            entry.insert(response);
+            log_str("Rebuttal posted successfully.");
+        } else {
+            log_str("Rebuttal already posted for this comment.");
+        }
+    }
+
+    // Function for reviewers to commit an updated vote while the rebuttal window is open
+    pub fn commit_vote_update(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        vote: String,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", vote, secret);
+        let hash = Sha256::digest(combined.as_bytes());
+        let commit = format!("{:x}", hash);
+
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let votes = &mut submission.submission_votes;
+        if env::signer_account_id().as_str() != reviewer {
+            log_str("Only the reviewer can update their own vote.");
+        } else if !submission.suggested_reviewers.contains(&reviewer) {
+            log_str("Reviewer is not assigned to this submission.");
+        } else if !votes.rebuttal_open() {
+            log_str("Rebuttal window is not open.");
+        } else if !votes.revealed_votes.contains_key(&reviewer) {
+            log_str("Only reviewers with a revealed vote can update it.");
+        } else if votes
+            .vote_update_commits
+            .iter()
+            .any(|vc| vc.reviewer == reviewer)
+        {
+            log_str("Duplicate vote update detected. Update not committed.");
+        } else {
+            votes
+                .vote_update_commits
+                .push(VoteCommit { reviewer, commit });
+            log_str("Vote update committed successfully.");
+        }
+    }
+
+    // Function for reviewers to reveal their updated vote after the rebuttal window closes
+    // Updates must be revealed within the reveal window that follows the rebuttal
+    // A reviewer who changes their vote loses the confidence and prediction from their ballot
+    pub fn reveal_vote_update(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        vote: String,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", vote, secret);
+        let hash = Sha256::digest(combined.as_bytes());
+        let commit = format!("{:x}", hash);
+
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let votes = &mut submission.submission_votes;
+        if env::signer_account_id().as_str() != reviewer {
+            log_str("Only the reviewer can update their own vote.");
+        } else if !submission.suggested_reviewers.contains(&reviewer) {
+            log_str("Reviewer is not assigned to this submission.");
+        } else if !votes.rebuttal_closed() {
+            log_str("Rebuttal window has not closed yet.");
+        } else if vote != "accept" && vote != "reject" {
+            log_str("Vote update reveal failed: Vote must be \"accept\" or \"reject\".");
+        } else if votes
+            .update_reveal_deadline
+            .is_some_and(|deadline| env::block_timestamp() > deadline)
+        {
+            log_str("Vote update reveal window has closed.");
+        } else if !votes.revealed_votes.contains_key(&reviewer) {
+            log_str("Only reviewers with a revealed vote can update it.");
+        } else if votes.revealed_updates.contains(&reviewer) {
+            log_str("Vote update already revealed.");
+        } else if let Some(update_commit) = votes
+            .vote_update_commits
+            .iter()
+            .find(|vc| vc.reviewer == reviewer)
+        {
+            if update_commit.commit == commit {
+                // A changed vote drops the confidence and prediction given for the old one
+                if votes.revealed_votes.get(&reviewer) != Some(&vote) {
+                    votes.revealed_confidences.remove(&reviewer);
+                    votes.revealed_predictions.remove(&reviewer);
+                }
+                votes.revealed_updates.push(reviewer.clone());
+                votes.revealed_votes.insert(reviewer, vote);
+                log_str("Vote update revealed successfully.");
+            } else {
+                log_str("Vote update reveal failed: Commit does not match.");
+            }
+        } else {
+            log_str("Vote update commit not found for reviewer.");
+        }
+    }
+
+    // Public method - lets the owner configure how long the rebuttal window stays open
+    pub fn set_rebuttal_window(&mut self, rebuttal_window: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.rebuttal_window = rebuttal_window;
+            log_str("Rebuttal window updated.");
+        } else {
+            log_str("Only the contract owner can change the rebuttal window.");
+        }
+    }
+
+    // Function to finalize the submission after all votes are revealed
+    // This function applies the submission's decision rule and sets the submission's accepted flag accordingly
+    pub fn finalize_submission(&mut self, submission_id: u64) {
+        let editor_deadline = env::block_timestamp() + self.editor_window;
+        let rule = self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .map(|sub| self.decision_rule_for(sub));
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id);
+        if let (Some(submission), Some(rule)) = (submission, rule) {
+            // A decision without quorum is final too, so scores and fees are applied once
+            if submission.decision.is_some() || submission.editor_decision.is_some() {
+                log_str("Submission has already been finalized.");
+            } else if submission.submission_votes.rebuttal_open() {
+                log_str("Rebuttal window is still open.");
+            } else if submission.submission_votes.awaiting_vote_updates() {
+                log_str("Vote updates are still being revealed.");
+            } else if submission.voting_ended && submission.reveals_pending() {
+                log_str("Reveal window is still open.");
+            } else if submission.voting_ended {
+                let votes = &submission.submission_votes.revealed_votes;
+                let revealed = votes.len() as u32;
+                let accept_votes = votes.values().filter(|vote| *vote == "accept").count() as u32;
+                let reject_votes = revealed - accept_votes;
+                // Commits without a valid reveal count as abstentions
+                let abstentions = submission.submission_votes.vote_commits.len() as u32 - revealed;
+                let vote_weights: HashMap<String, u32> = votes
+                    .keys()
+                    .map(|reviewer| {
+                        let weight = if rule.weight_by_confidence {
+                            submission
+                                .submission_votes
+                                .revealed_confidences
+                                .get(reviewer)
+                                .copied()
+                                .unwrap_or(DEFAULT_CONFIDENCE) as u32
+                        } else {
+                            1
+                        };
+                        (reviewer.clone(), weight)
+                    })
+                    .collect();
+                let accept_weight: u32 = votes
+                    .iter()
+                    .filter(|(_, vote)| *vote == "accept")
+                    .map(|(reviewer, _)| vote_weights[reviewer])
+                    .sum();
+                let reject_weight = vote_weights.values().sum::<u32>() - accept_weight;
+                let outcome = if revealed < rule.quorum {
+                    DecisionOutcome::NoQuorum
+                } else if rule.accepts(accept_weight, accept_weight + reject_weight) {
+                    DecisionOutcome::Accepted
+                } else {
+                    DecisionOutcome::Rejected
+                };
+                let rewards = Self::reward_shares(
+                    &self.reward_formula,
+                    &submission.suggested_reviewers,
+                    votes,
+                    &outcome,
+                    submission.review_fee,
+                );
+                submission.accepted = match outcome {
+                    DecisionOutcome::Accepted => Some(true),
+                    DecisionOutcome::Rejected => Some(false),
+                    DecisionOutcome::NoQuorum => None,
+                };
+                match outcome {
+                    DecisionOutcome::Accepted => log_str("Submission accepted."),
+                    DecisionOutcome::Rejected => log_str("Submission rejected."),
+                    DecisionOutcome::NoQuorum => log_str("Quorum not met. No decision reached."),
+                }
+                submission.decision = Some(Decision {
+                    rule,
+                    accept_votes,
+                    reject_votes,
+                    abstentions,
+                    vote_weights,
+                    accept_weight,
+                    reject_weight,
+                    outcome,
+                });
+                // The deposit settles once the editor window closes, or earlier on a final outcome
+                submission.editor_deadline = Some(editor_deadline);
+                submission.score_aggregates = Self::aggregate_scores(
+                    &submission.scoring_rubric,
+                    &submission.submission_votes.revealed_scores,
+                );
+                let scores = Self::truthfulness_scores(
+                    &submission.submission_votes.revealed_votes,
+                    &submission.submission_votes.revealed_predictions,
+                );
+                for (name, score) in &scores {
+                    if let Some(profile) = self.reviewers.iter_mut().find(|r| r.name == *name) {
+                        profile.truthfulness_score += score;
+                        profile.scored_reviews += 1;
+                    }
+                }
+                submission.submission_votes.truthfulness_scores = scores;
+                // Fees are settled once, so later review rounds do not pay them again
+                if submission.fees_settled {
+                    return;
+                }
+                submission.fees_settled = true;
+                let token = submission.fee_token.clone();
+                // Whatever is not paid out, including rounding leftovers, goes back to the author
+                let refund =
+                    submission.review_fee - rewards.iter().map(|(_, a)| a).sum::<Balance>();
+                if refund > 0 {
+                    submission.reward_refunded = refund;
+                    self.ledger.push(LedgerEntry::new(
+                        LedgerKind::Refunded,
+                        &submission.author,
+                        Some(submission_id),
+                        refund,
+                        token.as_ref(),
+                    ));
+                    Self::pay(token.as_ref(), &submission.author, refund);
+                }
+                for (reviewer, amount) in rewards {
+                    submission.reward_payouts.insert(reviewer.clone(), amount);
+                    self.ledger.push(LedgerEntry::new(
+                        LedgerKind::RewardPaid,
+                        &reviewer,
+                        Some(submission_id),
+                        amount,
+                        token.as_ref(),
+                    ));
+                    Self::pay(token.as_ref(), &reviewer, amount).then(
+                        Self::ext(env::current_account_id())
+                            .with_static_gas(GAS_FOR_REWARD_CALLBACK)
+                            .on_reward_transfer(submission_id, reviewer, U128(amount)),
+                    );
+                }
+                self.draw_bounties(submission_id);
+            } else {
+                log_str("Voting has not ended yet.");
+            }
+        } else {
+            env::panic_str("Submission not found.");
+        }
+    }
+
+    // Public method - lets the owner choose how review fees are split among reviewers
+    pub fn set_reward_formula(&mut self, formula: RewardFormula) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.reward_formula = formula;
+            log_str("Reward formula updated.");
+        } else {
+            log_str("Only the contract owner can change the reward formula.");
+        }
+    }
+
+    // Callback - refunds the author when a reviewer reward transfer fails
+    #[private]
+    pub fn on_reward_transfer(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_ok() {
+            return;
+        }
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        submission.reward_payouts.remove(&reviewer);
+        submission.reward_refunded += amount.0;
+        let token = submission.fee_token.as_ref();
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::RewardReturned,
+            &reviewer,
+            Some(submission_id),
+            amount.0,
+            token,
+        ));
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::Refunded,
+            &submission.author,
+            Some(submission_id),
+            amount.0,
+            token,
+        ));
+        Self::pay(token, &submission.author, amount.0);
+        log_str("Reward transfer failed. Refunded to the author.");
+    }
+
+    // Public method - lets a sponsor open a bounty pool for submissions matching keywords or a track
+    // The attached deposit is the pool's initial balance; returns the pool ID
+    #[payable]
+    pub fn create_bounty_pool(
+        &mut self,
+        keywords: Vec<String>,
+        track: Option<String>,
+        reward_per_submission: U128,
+    ) -> u64 {
+        let pool_id = self.open_bounty_pool(
+            env::signer_account_id().to_string(),
+            keywords,
+            track,
+            reward_per_submission,
+            None,
+        );
+        self.fund_bounty_pool(pool_id);
+        pool_id
+    }
+
+    // Public method - adds the attached deposit to a bounty pool
+    #[payable]
+    pub fn fund_bounty_pool(&mut self, pool_id: u64) {
+        if self
+            .bounty_pools
+            .get(pool_id as usize)
+            .is_some_and(|pool| pool.token.is_some())
+        {
+            env::panic_str("Bounty pool holds a fungible token. Use ft_transfer_call.");
+        }
+        self.credit_pool(
+            pool_id,
+            env::signer_account_id().to_string(),
+            env::attached_deposit(),
+        );
+    }
+
+    // Public method - returns a bounty pool with its balance and history
+    pub fn get_bounty_pool(&self, pool_id: u64) -> Option<BountyPool> {
+        self.bounty_pools.get(pool_id as usize).cloned()
+    }
+
+    // Public method - returns all bounty pools with their IDs
+    pub fn get_bounty_pools(&self) -> Vec<(u64, BountyPool)> {
+        self.bounty_pools
+            .iter()
+            .enumerate()
+            .map(|(id, pool)| (id as u64, pool.clone()))
+            .collect()
+    }
+
+    // Callback - keeps a forfeited deposit share in the treasury when its transfer to a reviewer fails
+    #[private]
+    pub fn on_deposit_transfer(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_ok() {
+            return;
+        }
+        let submission = self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let token = submission.fee_token.as_ref();
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::RewardReturned,
+            &reviewer,
+            Some(submission_id),
+            amount.0,
+            token,
+        ));
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::Slashed,
+            &submission.author,
+            Some(submission_id),
+            amount.0,
+            token,
+        ));
+        self.slashed_stake += amount.0;
+        log_str("Deposit transfer failed. Kept by the contract.");
+    }
+
+    // Callback - returns a failed bounty payout to its pool
+    #[private]
+    pub fn on_bounty_transfer(
+        &mut self,
+        pool_id: u64,
+        submission_id: u64,
+        reviewer: String,
+        amount: U128,
+        #[callback_result] result: Result<(), PromiseError>,
+    ) {
+        if result.is_ok() {
+            return;
+        }
+        let pool = self
+            .bounty_pools
+            .get_mut(pool_id as usize)
+            .unwrap_or_else(|| env::panic_str("Bounty pool not found."));
+        pool.balance.0 += amount.0;
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::RewardReturned,
+            &reviewer,
+            Some(submission_id),
+            amount.0,
+            pool.token.as_ref(),
+        ));
+        pool.history.push(PoolEntry {
+            kind: PoolEntryKind::Refund,
+            account: reviewer,
+            submission_id: Some(submission_id),
+            amount,
+            timestamp: env::block_timestamp(),
+        });
+        log_str("Bounty transfer failed. Refunded to the pool.");
+    }
+
+    // Public method - lets the owner accept a NEP-141 token and set its review fee, or remove it with None
+    pub fn set_accepted_token(&mut self, token: AccountId, review_fee: Option<U128>) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change accepted tokens.");
+        } else if let Some(review_fee) = review_fee {
+            self.accepted_tokens.insert(token.to_string(), review_fee.0);
+            log_str("Token accepted.");
+        } else {
+            self.accepted_tokens.remove(token.as_str());
+            log_str("Token removed.");
+        }
+    }
+
+    // NEP-141 receiver - accepts whitelisted tokens for submission fees and bounty pools
+    // The msg is a JSON TokenAction; the unused part of the amount is returned to the sender
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token = env::predecessor_account_id().to_string();
+        let review_fee = *self
+            .accepted_tokens
+            .get(&token)
+            .unwrap_or_else(|| env::panic_str("Token is not accepted."));
+        let action: TokenAction = near_sdk::serde_json::from_str(&msg)
+            .unwrap_or_else(|_| env::panic_str("Invalid transfer message."));
+        let unused = match action {
+            TokenAction::Submit { data, track } => {
+                if amount.0 < review_fee {
+                    env::panic_str("Transferred amount does not cover the submission fee.");
+                }
+                if track
+                    .as_ref()
+                    .is_some_and(|track| !self.tracks.contains_key(track))
+                {
+                    env::panic_str("Track not found.");
+                }
+                // Token submissions pay only the review fee, without a refundable deposit
+                self.create_submission(
+                    sender_id.to_string(),
+                    data,
+                    track,
+                    review_fee,
+                    0,
+                    Some(token),
+                );
+                amount.0 - review_fee
+            }
+            TokenAction::CreatePool {
+                keywords,
+                track,
+                reward_per_submission,
+            } => {
+                let pool_id = self.open_bounty_pool(
+                    sender_id.to_string(),
+                    keywords,
+                    track,
+                    reward_per_submission,
+                    Some(token),
+                );
+                self.credit_pool(pool_id, sender_id.to_string(), amount.0);
+                0
+            }
+            TokenAction::FundPool { pool_id } => {
+                let pool = self
+                    .bounty_pools
+                    .get(pool_id as usize)
+                    .unwrap_or_else(|| env::panic_str("Bounty pool not found."));
+                if pool.token.as_ref() != Some(&token) {
+                    env::panic_str("Bounty pool does not hold this token.");
+                }
+                self.credit_pool(pool_id, sender_id.to_string(), amount.0);
+                0
+            }
+        };
+        PromiseOrValue::Value(U128(unused))
+    }
+
+    // Public method - lets the submission's editor reject it before review
+    // The review fee is refunded; marking it as spam forfeits the deposit to the treasury
+    pub fn desk_reject(&mut self, submission_id: u64, justification: String, spam: bool) {
+        let editor = env::signer_account_id().to_string();
+        let index = self
+            .submissions
+            .iter()
+            .position(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if self.editor_for(&self.submissions[index]).as_ref() != Some(&editor) {
+            log_str("Only the submission's editor can desk reject it.");
+            return;
+        }
+        let submission = &mut self.submissions[index];
+        if submission.accepted.is_some()
+            || submission.decision.is_some()
+            || submission.desk_rejection.is_some()
+        {
+            log_str("Submission has already been decided.");
+        } else if justification.trim().is_empty() {
+            log_str("A written justification is required.");
+        } else {
+            submission.desk_rejection = Some(DeskRejection {
+                editor,
+                justification,
+                spam,
+            });
+            submission.accepted = Some(false);
+            submission.voting_ended = true;
+            submission.fees_settled = true;
+            if submission.review_fee > 0 {
+                submission.reward_refunded = submission.review_fee;
+                self.ledger.push(LedgerEntry::new(
+                    LedgerKind::Refunded,
+                    &submission.author,
+                    Some(submission_id),
+                    submission.review_fee,
+                    submission.fee_token.as_ref(),
+                ));
+                Self::pay(
+                    submission.fee_token.as_ref(),
+                    &submission.author,
+                    submission.review_fee,
+                );
+            }
+            log_str("Submission desk rejected.");
+            self.settle_deposit(submission_id);
+        }
+    }
+
+    // Public method - settles the author's deposit on the final outcome once the editor window closes
+    // Anyone can call this; a pending appeal settles the deposit when it is decided instead
+    pub fn settle_submission_deposit(&mut self, submission_id: u64) {
+        let submission = self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if submission.deposit_settled {
+            log_str("Deposit has already been settled.");
+        } else if !submission.editor_window_closed() {
+            log_str("Editor window has not closed yet.");
+        } else if submission
+            .appeal
+            .as_ref()
+            .is_some_and(|appeal| appeal.outcome.is_none())
+        {
+            log_str("An appeal is still pending.");
+        } else {
+            self.settle_deposit(submission_id);
+        }
+    }
+
+    // Public method - lets the owner configure how long finalized outcomes stay open to editors and appeals
+    pub fn set_editor_window(&mut self, editor_window: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.editor_window = editor_window;
+            log_str("Editor window updated.");
+        } else {
+            log_str("Only the contract owner can change the editor window.");
+        }
+    }
+
+    // Public method - lets the owner set the contract-wide deposit refund policy
+    pub fn set_refund_policy(&mut self, policy: RefundPolicy) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change the refund policy.");
+        } else if policy.accept_refund_percent > 100 || policy.reject_refund_percent > 100 {
+            log_str("Invalid refund policy.");
+        } else {
+            self.refund_policy = policy;
+            log_str("Refund policy updated.");
+        }
+    }
+
+    // Public method - lets the owner set or clear a track's deposit refund policy
+    pub fn set_track_refund_policy(&mut self, track: String, policy: Option<RefundPolicy>) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change the refund policy.");
+        } else if policy.as_ref().is_some_and(|policy| {
+            policy.accept_refund_percent > 100 || policy.reject_refund_percent > 100
+        }) {
+            log_str("Invalid refund policy.");
+        } else if let Some(config) = self.tracks.get_mut(&track) {
+            config.refund_policy = policy;
+            log_str("Track refund policy updated.");
+        } else {
+            env::panic_str("Track not found.");
+        }
+    }
+
+    // Public method - returns ledger entries with their IDs, starting at from_index
+    pub fn get_ledger(&self, from_index: u64, limit: u64) -> Vec<(u64, LedgerEntry)> {
+        Self::paginate(self.ledger.iter().enumerate().collect(), from_index, limit)
+    }
+
+    // Public method - returns the ledger entries of one account, starting at from_index
+    pub fn get_account_ledger(
+        &self,
+        account_id: String,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<(u64, LedgerEntry)> {
+        let entries = self
+            .ledger
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.account == account_id)
+            .collect();
+        Self::paginate(entries, from_index, limit)
+    }
+
+    // Public method - returns the ledger entries of one submission, starting at from_index
+    pub fn get_submission_ledger(
+        &self,
+        submission_id: u64,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<(u64, LedgerEntry)> {
+        let entries = self
+            .ledger
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.submission_id == Some(submission_id))
+            .collect();
+        Self::paginate(entries, from_index, limit)
+    }
+
+    // Public method - returns an account's total per ledger kind, in NEAR or the given token
+    pub fn get_account_totals(
+        &self,
+        account_id: String,
+        token: Option<String>,
+    ) -> Vec<(LedgerKind, U128)> {
+        Self::ledger_totals(
+            self.ledger
+                .iter()
+                .filter(|entry| entry.account == account_id && entry.token == token)
+                .collect(),
+        )
+    }
+
+    // Public method - returns a submission's total per ledger kind, in NEAR or the given token
+    pub fn get_submission_totals(
+        &self,
+        submission_id: u64,
+        token: Option<String>,
+    ) -> Vec<(LedgerKind, U128)> {
+        Self::ledger_totals(
+            self.ledger
+                .iter()
+                .filter(|entry| entry.submission_id == Some(submission_id) && entry.token == token)
+                .collect(),
+        )
+    }
+
+    // Public method - compares the NEAR the contract holds for others with its account balance
+    pub fn get_treasury_report(&self) -> TreasuryReport {
+        let stakes: Balance = self.reviewers.iter().map(|r| r.stake + r.unbonding).sum();
+        let storage_deposits: Balance = self.storage_deposits.values().sum();
+        let bounty_pools: Balance = self
+            .bounty_pools
+            .iter()
+            .filter(|pool| pool.token.is_none())
+            .map(|pool| pool.balance.0)
+            .sum();
+        let escrow: Balance = self
+            .submissions
+            .iter()
+            .filter(|sub| sub.fee_token.is_none())
+            .map(|sub| {
+                let fees = if sub.fees_settled { 0 } else { sub.review_fee };
+                let deposit = if sub.deposit_settled { 0 } else { sub.deposit };
+                let appeal = match &sub.appeal {
+                    Some(appeal) if appeal.outcome.is_none() => appeal.deposit.0,
+                    _ => 0,
+                };
+                fees + deposit + appeal
+            })
+            .sum();
+        let account_balance = env::account_balance();
+        let obligations = stakes + storage_deposits + bounty_pools + escrow;
+        TreasuryReport {
+            account_balance: U128(account_balance),
+            stakes: U128(stakes),
+            storage_deposits: U128(storage_deposits),
+            bounty_pools: U128(bounty_pools),
+            escrow: U128(escrow),
+            slashed: U128(self.slashed_stake),
+            surplus: U128(account_balance.saturating_sub(obligations)),
+            solvent: account_balance >= obligations,
+        }
+    }
+
+    // Public method - lets the owner assign or clear the editor of a track
+    pub fn set_track_editor(&mut self, track: String, editor: Option<String>) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can assign editors.");
+        } else if let Some(config) = self.tracks.get_mut(&track) {
+            config.editor = editor;
+            log_str("Track editor updated.");
+        } else {
+            env::panic_str("Track not found.");
+        }
+    }
+
+    // Public method - lets the owner assign or clear the editor of a single submission
+    pub fn set_submission_editor(&mut self, submission_id: u64, editor: Option<String>) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can assign editors.");
+        } else if let Some(submission) = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+        {
+            submission.editor = editor;
+            log_str("Submission editor updated.");
+        } else {
+            env::panic_str("Submission not found.");
+        }
+    }
+
+    // Public method - lets the submission's editor make the final call once the decision rule has been applied
+    // A verdict that differs from the mechanical outcome is recorded as an override
+    pub fn issue_editor_decision(
+        &mut self,
+        submission_id: u64,
+        verdict: EditorVerdict,
+        justification: String,
+    ) {
+        let editor = env::signer_account_id().to_string();
+        let index = self
+            .submissions
+            .iter()
+            .position(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if self.editor_for(&self.submissions[index]).as_ref() != Some(&editor) {
+            log_str("Only the submission's editor can issue a decision.");
+            return;
+        }
+        let submission = &mut self.submissions[index];
+        let mechanical = match &submission.decision {
+            Some(decision) => decision.outcome.clone(),
+            None => {
+                log_str("Reveals have not closed yet.");
+                return;
+            }
+        };
+        if submission.editor_decision.is_some() {
+            log_str("Editor decision has already been issued.");
+        } else if submission.editor_window_closed() {
+            log_str("Editor window has closed.");
+        } else if justification.trim().is_empty() {
+            log_str("A written justification is required.");
+        } else {
+            let overrides_mechanical = match mechanical {
+                DecisionOutcome::Accepted => verdict != EditorVerdict::Accept,
+                DecisionOutcome::Rejected => verdict != EditorVerdict::Reject,
+                DecisionOutcome::NoQuorum => false,
+            };
+            submission.accepted = match verdict {
+                EditorVerdict::Accept => Some(true),
+                EditorVerdict::Reject => Some(false),
+                EditorVerdict::Revise => None,
+            };
+            submission.revisions_requested = verdict == EditorVerdict::Revise;
+            // Acceptances cannot be appealed, so the deposit settles right away
+            let settle = verdict == EditorVerdict::Accept;
+            submission.editor_decision = Some(EditorDecision {
+                editor,
+                verdict,
+                justification,
+                overrides_mechanical,
+            });
+            if overrides_mechanical {
+                log_str("Editor decision issued. It overrides the mechanical outcome.");
+            } else {
+                log_str("Editor decision issued.");
+            }
+            if settle {
+                self.settle_deposit(submission_id);
+            }
+        }
+    }
+
+    // Public method - returns the editor decision recorded for a submission, if any
+    pub fn get_editor_decision(&self, submission_id: u64) -> Option<EditorDecision> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .editor_decision
+            .clone()
+    }
+
+    // Public method - lets the author upload a new version after revisions were requested
+    // The current round is archived and the same panel starts a new commit-reveal round
+    pub fn submit_revision(&mut self, submission_id: u64, data: String) {
+        let commit_deadline = env::block_timestamp() + self.commit_window;
+        let comment_commit_deadline = env::block_timestamp() + self.comment_commit_window;
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if submission.author != env::signer_account_id().to_string() {
+            log_str("Only the author can submit a revision.");
+        } else if !submission.revisions_requested {
+            log_str("Revisions have not been requested.");
+        } else {
+            submission.history.push(ReviewRound {
+                round: submission.round,
+                response: std::mem::replace(&mut submission.response, data),
+                submission_votes: std::mem::replace(
+                    &mut submission.submission_votes,
+                    SubmissionVote {
+                        submission_id,
+                        ..Default::default()
+                    },
+                ),
+                decision: submission.decision.take(),
+                score_aggregates: std::mem::take(&mut submission.score_aggregates),
+                editor_decision: submission.editor_decision.take(),
+            });
+            submission.round += 1;
+            submission.revisions_requested = false;
+            submission.voting_ended = false;
+            submission.reveal_deadline = None;
+            submission.editor_deadline = None;
+            submission.comment_commit_deadline = comment_commit_deadline;
+            submission.comment_reveal_deadline =
+                comment_commit_deadline + self.comment_reveal_window;
+            submission.accepted = None;
+            submission.commit_deadlines = submission
+                .suggested_reviewers
+                .iter()
+                .map(|name| (name.clone(), commit_deadline))
+                .collect();
+            log_str("Revision submitted. A new review round has started.");
+        }
+    }
+
+    // Public method - returns the text, votes and comments of a submission's review round
+    pub fn get_review_round(&self, submission_id: u64, round: u32) -> Option<ReviewRound> {
+        let submission = self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        if round == submission.round {
+            Some(ReviewRound {
+                round,
+                response: submission.response.clone(),
+                submission_votes: submission.submission_votes.clone(),
+                decision: submission.decision.clone(),
+                score_aggregates: submission.score_aggregates.clone(),
+                editor_decision: submission.editor_decision.clone(),
+            })
+        } else {
+            submission
+                .history
+                .iter()
+                .find(|past| past.round == round)
+                .cloned()
+        }
+    }
+
+    // Public method - lets the author of a rejected submission appeal, optionally attaching a deposit
+    // A fresh panel that excludes the original reviewers is drawn for the appeal vote
+    #[payable]
+    pub fn file_appeal(&mut self, submission_id: u64, justification: String) {
+        let index = self
+            .submissions
+            .iter()
+            .position(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let submission = &self.submissions[index];
+        if submission.author != env::signer_account_id().to_string() {
+            env::panic_str("Only the author can file an appeal.");
+        }
+        if submission.accepted != Some(false) {
+            env::panic_str("Only rejected submissions can be appealed.");
+        }
+        if submission.appeal.is_some() {
+            env::panic_str("An appeal has already been filed.");
+        }
+        if !submission.editor_window_open() {
+            env::panic_str("The appeal window has closed.");
+        }
+        if justification.trim().is_empty() {
+            env::panic_str("A written justification is required.");
+        }
+        let panel = self.appeal_panel(submission);
+        if panel.is_empty() {
+            env::panic_str("No eligible reviewers for an appeal panel.");
+        }
+        let deposit = env::attached_deposit();
+        if deposit > 0 {
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::DepositCollected,
+                env::signer_account_id().as_ref(),
+                Some(submission_id),
+                deposit,
+                None,
+            ));
+        }
+        let commit_deadline = env::block_timestamp() + self.commit_window;
+        self.submissions[index].appeal = Some(Appeal {
+            justification,
+            deposit: U128(deposit),
+            panel,
+            vote_commits: Vec::new(),
+            revealed_votes: HashMap::new(),
+            outcome: None,
+            refunded: U128(0),
+            commit_deadline,
+            reveal_deadline: commit_deadline + self.reveal_window,
+        });
+        log_str("Appeal filed successfully.");
+    }
+
+    // Function for appeal panel members to commit their vote ("uphold" or "overturn")
+    pub fn commit_appeal_vote(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        vote: String,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", vote, secret);
+        let hash = Sha256::digest(combined.as_bytes());
+        let commit = format!("{:x}", hash);
+
+        let appeal = self.appeal_mut(submission_id);
+        if env::signer_account_id().as_str() != reviewer {
+            log_str("Only the reviewer can cast their own appeal vote.");
+        } else if !appeal.panel.contains(&reviewer) {
+            log_str("Reviewer is not on the appeal panel.");
+        } else if env::block_timestamp() > appeal.commit_deadline {
+            log_str("Appeal commit window has closed.");
+        } else if appeal.vote_commits.iter().any(|vc| vc.reviewer == reviewer) {
+            log_str("Duplicate vote commit detected. Vote not committed.");
+        } else {
+            appeal.vote_commits.push(VoteCommit { reviewer, commit });
+            log_str("Appeal vote committed successfully.");
+        }
+    }
+
+    // Function for appeal panel members to reveal their vote once the whole panel has committed
+    // or the commit window has closed, and before the reveal deadline
+    pub fn reveal_appeal_vote(
+        &mut self,
+        submission_id: u64,
+        reviewer: String,
+        vote: String,
+        secret: String,
+    ) {
+        let combined = format!("{}{}", vote, secret);
+        let hash = Sha256::digest(combined.as_bytes());
+        let commit = format!("{:x}", hash);
+
+        let appeal = self.appeal_mut(submission_id);
+        if env::signer_account_id().as_str() != reviewer {
+            log_str("Only the reviewer can cast their own appeal vote.");
+        } else if !appeal.panel.contains(&reviewer) {
+            log_str("Reviewer is not on the appeal panel.");
+        } else if appeal.vote_commits.len() < appeal.panel.len()
+            && env::block_timestamp() <= appeal.commit_deadline
+        {
+            log_str("Not all appeal reviewers have committed their votes.");
+        } else if env::block_timestamp() > appeal.reveal_deadline {
+            log_str("Appeal reveal window has closed.");
+        } else if vote != "uphold" && vote != "overturn" {
+            log_str("Appeal vote must be \"uphold\" or \"overturn\".");
+        } else if let Some(vote_commit) = appeal
+            .vote_commits
+            .iter()
+            .find(|vc| vc.reviewer == reviewer)
+        {
+            if vote_commit.commit == commit {
+                appeal.revealed_votes.insert(reviewer, vote);
+                log_str("Appeal vote revealed successfully.");
+            } else {
+                log_str("Vote reveal failed: Commit does not match.");
+            }
+        } else {
+            log_str("Vote commit not found for reviewer.");
+        }
+    }
+
+    // Function to settle an appeal once a majority of the panel has revealed the same vote
+    // After the reveal deadline, the revealed votes decide and everyone else abstains
+    // An overturned rejection accepts the submission and refunds the appeal deposit
+    pub fn finalize_appeal(&mut self, submission_id: u64) {
+        let submission = self
+            .submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."));
+        let author = submission.author.clone();
+        let appeal = match submission.appeal.as_mut() {
+            Some(appeal) => appeal,
+            None => env::panic_str("Appeal not found."),
+        };
+        if appeal.outcome.is_some() {
+            log_str("Appeal has already been decided.");
+            return;
+        }
+        let panel_size = appeal.panel.len();
+        let overturn_votes = appeal
+            .revealed_votes
+            .values()
+            .filter(|vote| *vote == "overturn")
+            .count();
+        let uphold_votes = appeal.revealed_votes.len() - overturn_votes;
+        let reveals_closed = env::block_timestamp() > appeal.reveal_deadline;
+        if overturn_votes * 2 > panel_size || (reveals_closed && overturn_votes > uphold_votes) {
+            appeal.outcome = Some(AppealOutcome::Overturned);
+            submission.accepted = Some(true);
+            if appeal.deposit.0 > 0 {
+                appeal.refunded = appeal.deposit;
+                self.ledger.push(LedgerEntry::new(
+                    LedgerKind::Refunded,
+                    &author,
+                    Some(submission_id),
+                    appeal.deposit.0,
+                    None,
+                ));
+                Promise::new(author.parse().unwrap()).transfer(appeal.deposit.0);
+            }
+            log_str("Appeal succeeded. Rejection overturned.");
+        } else if uphold_votes * 2 >= panel_size || reveals_closed {
+            appeal.outcome = Some(AppealOutcome::Upheld);
+            // The contract keeps the deposit, recorded like slashed stake
+            if appeal.deposit.0 > 0 {
+                self.slashed_stake += appeal.deposit.0;
+                self.ledger.push(LedgerEntry::new(
+                    LedgerKind::Slashed,
+                    &author,
+                    Some(submission_id),
+                    appeal.deposit.0,
+                    None,
+                ));
+            }
+            log_str("Appeal failed. Rejection upheld.");
+        } else {
+            log_str("Not enough appeal votes have been revealed.");
+            return;
+        }
+        self.settle_deposit(submission_id);
+    }
+
+    // Public method - returns the appeal filed for a submission, if any
+    pub fn get_appeal(&self, submission_id: u64) -> Option<Appeal> {
+        self.submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .appeal
+            .clone()
+    }
+
+    // Public method - lets a reviewer add the attached deposit to their stake
+    #[payable]
+    pub fn stake(&mut self) {
+        let signer = env::signer_account_id().to_string();
+        let profile = self
+            .reviewers
+            .iter_mut()
+            .find(|r| r.name == signer)
+            .unwrap_or_else(|| env::panic_str("Only reviewers can stake."));
+        let amount = env::attached_deposit();
+        if amount == 0 {
+            env::panic_str("Attach a deposit to stake.");
+        }
+        profile.stake += amount;
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::StakeAdded,
+            &signer,
+            None,
+            amount,
+            None,
+        ));
+        log_str("Stake added successfully.");
+    }
+
+    // Public method - starts unbonding part of the signer's stake
+    // Unbonding funds stay slashable until the unbonding period has passed
+    pub fn request_unstake(&mut self, amount: U128) {
+        let signer = env::signer_account_id().to_string();
+        let unbonding_until = env::block_timestamp() + self.unbonding_period;
+        match self.reviewers.iter_mut().find(|r| r.name == signer) {
+            Some(profile) if profile.stake >= amount.0 => {
+                profile.stake -= amount.0;
+                profile.unbonding += amount.0;
+                profile.unbonding_until = unbonding_until;
+                log_str("Unstake requested. Funds are unbonding.");
+            }
+            Some(_) => log_str("Cannot unstake more than the staked amount."),
+            None => log_str("Reviewer not found."),
+        }
+    }
+
+    // Public method - sends the signer's unbonded stake back once the unbonding period has passed
+    pub fn withdraw_stake(&mut self) {
+        let signer = env::signer_account_id().to_string();
+        let has_pending_report = self
+            .misconduct_reports
+            .iter()
+            .any(|report| report.reviewer == signer && report.status == ReportStatus::Pending);
+        let profile = match self.reviewers.iter_mut().find(|r| r.name == signer) {
+            Some(profile) => profile,
+            None => env::panic_str("Reviewer not found."),
+        };
+        if profile.unbonding == 0 {
+            log_str("No unbonded stake to withdraw.");
+        } else if env::block_timestamp() < profile.unbonding_until {
+            log_str("Stake is still unbonding.");
+        } else if has_pending_report {
+            log_str("Stake cannot be withdrawn while a misconduct report is pending.");
+        } else {
+            let amount = std::mem::take(&mut profile.unbonding);
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::StakeWithdrawn,
+                &signer,
+                None,
+                amount,
+                None,
+            ));
+            Promise::new(env::signer_account_id()).transfer(amount);
+            log_str("Stake withdrawn successfully.");
+        }
+    }
+
+    // Public method - lets the owner or any track editor set the stake needed for assignment
+    pub fn set_min_stake(&mut self, min_stake: U128) {
+        if self.is_owner_or_editor() {
+            self.min_stake = min_stake.0;
+            log_str("Minimum stake updated.");
+        } else {
+            log_str("Only the contract owner or an editor can change the minimum stake.");
+        }
+    }
+
+    // Public method - lets the owner configure how long unstaked funds stay slashable
+    pub fn set_unbonding_period(&mut self, unbonding_period: u64) {
+        if env::signer_account_id() == env::current_account_id() {
+            self.unbonding_period = unbonding_period;
+            log_str("Unbonding period updated.");
+        } else {
+            log_str("Only the contract owner can change the unbonding period.");
+        }
+    }
+
+    // Public method - lets the owner configure the share of stake slashed per penalty
+    pub fn set_slash_percent(&mut self, slash_percent: u8) {
+        if env::signer_account_id() != env::current_account_id() {
+            log_str("Only the contract owner can change the slash percentage.");
+        } else if slash_percent > 100 {
+            log_str("Slash percentage cannot exceed 100.");
+        } else {
+            self.slash_percent = slash_percent;
+            log_str("Slash percentage updated.");
+        }
+    }
+
+    // Public method - lets anyone report misconduct by a reviewer on a submission and returns the report ID
+    pub fn report_misconduct(
+        &mut self,
+        reviewer: String,
+        submission_id: u64,
+        reason: String,
+    ) -> u64 {
+        if !self.reviewers.iter().any(|r| r.name == reviewer) {
+            env::panic_str("Reviewer not found.");
+        }
+        if !self
+            .submissions
+            .iter()
+            .any(|sub| sub.submission_votes.submission_id == submission_id)
+        {
+            env::panic_str("Submission not found.");
+        }
+        if reason.trim().is_empty() {
+            env::panic_str("A reason is required.");
+        }
+        self.misconduct_reports.push(MisconductReport {
+            reporter: env::signer_account_id().to_string(),
+            reviewer,
+            submission_id,
+            reason,
+            status: ReportStatus::Pending,
+            slashed: U128(0),
+        });
+        log_str("Misconduct report filed.");
+        (self.misconduct_reports.len() - 1) as u64
+    }
+
+    // Public method - lets the owner or an editor uphold or dismiss a pending misconduct report
+    // Upholding a report slashes the reviewer's stake
+    pub fn resolve_misconduct_report(&mut self, report_id: u64, upheld: bool) {
+        if !self.is_owner_or_editor() {
+            log_str("Only the contract owner or an editor can resolve misconduct reports.");
+            return;
+        }
+        let report = self
+            .misconduct_reports
+            .get(report_id as usize)
+            .unwrap_or_else(|| env::panic_str("Report not found."));
+        if report.status != ReportStatus::Pending {
+            log_str("Report has already been resolved.");
+            return;
+        }
+        let reviewer = report.reviewer.clone();
+        let slashed = if upheld { self.slash(&reviewer) } else { 0 };
+        let report = &mut self.misconduct_reports[report_id as usize];
+        if upheld {
+            report.status = ReportStatus::Upheld;
+            report.slashed = U128(slashed);
+            log_str("Misconduct report upheld.");
+        } else {
+            report.status = ReportStatus::Dismissed;
+            log_str("Misconduct report dismissed.");
+        }
+    }
+
+    // Public method - returns all misconduct reports that have not been resolved yet
+    pub fn get_pending_misconduct_reports(&self) -> Vec<(u64, MisconductReport)> {
+        self.misconduct_reports
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.status == ReportStatus::Pending)
+            .map(|(id, r)| (id as u64, r.clone()))
+            .collect()
+    }
+
+    // Removes the configured share of a reviewer's staked and unbonding funds and returns the amount
+    fn slash(&mut self, name: &str) -> Balance {
+        let slash_percent = self.slash_percent as u128;
+        let profile = match self.reviewers.iter_mut().find(|r| r.name == name) {
+            Some(profile) => profile,
+            None => return 0,
+        };
+        let from_stake = profile.stake * slash_percent / 100;
+        let from_unbonding = profile.unbonding * slash_percent / 100;
+        profile.stake -= from_stake;
+        profile.unbonding -= from_unbonding;
+        let slashed = from_stake + from_unbonding;
+        if slashed > 0 {
+            self.slashed_stake += slashed;
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::Slashed,
+                name,
+                None,
+                slashed,
+                None,
+            ));
+        }
+        slashed
+    }
+
+    // Returns whether a reviewer has staked enough to be assigned to submissions
+    fn has_min_stake(&self, name: &str) -> bool {
+        self.min_stake == 0
+            || self
+                .reviewers
+                .iter()
+                .any(|r| r.name == name && r.stake >= self.min_stake)
+    }
+
+    // Sends NEAR, or the given NEP-141 token, to an account
+    fn pay(token: Option<&String>, receiver: &str, amount: Balance) -> Promise {
+        match token {
+            Some(token) => ext_ft::ext(token.parse().unwrap())
+                .with_attached_deposit(1)
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(receiver.parse().unwrap(), U128(amount), None),
+            None => Promise::new(receiver.parse().unwrap()).transfer(amount),
+        }
+    }
+
+    // Checks the attached deposit covers the NEAR review fee and submission deposit, refunding any excess
+    fn collect_submission_fee(&self) -> (Balance, Balance) {
+        let attached = env::attached_deposit();
+        let required = self.review_fee + self.submission_deposit;
+        if attached < required {
+            env::panic_str("Attached deposit does not cover the submission fee.");
+        }
+        if attached > required {
+            Promise::new(env::predecessor_account_id()).transfer(attached - required);
+        }
+        (self.review_fee, self.submission_deposit)
+    }
+
+    // Records a new, empty bounty pool and returns its ID
+    fn open_bounty_pool(
+        &mut self,
+        sponsor: String,
+        keywords: Vec<String>,
+        track: Option<String>,
+        reward_per_submission: U128,
+        token: Option<String>,
+    ) -> u64 {
+        if keywords.is_empty() && track.is_none() {
+            env::panic_str("A bounty pool needs keywords or a track.");
+        }
+        if track
+            .as_ref()
+            .is_some_and(|track| !self.tracks.contains_key(track))
+        {
+            env::panic_str("Track not found.");
+        }
+        if reward_per_submission.0 == 0 {
+            env::panic_str("The reward per submission must be positive.");
+        }
+        self.bounty_pools.push(BountyPool {
+            sponsor,
+            keywords,
+            track,
+            reward_per_submission,
+            balance: U128(0),
+            history: Vec::new(),
+            token,
+        });
+        (self.bounty_pools.len() - 1) as u64
+    }
+
+    // Adds a sponsor's deposit to a bounty pool and records it in the pool's history
+    fn credit_pool(&mut self, pool_id: u64, sponsor: String, amount: Balance) {
+        if amount == 0 {
+            env::panic_str("Attach a deposit to fund the pool.");
+        }
+        let pool = self
+            .bounty_pools
+            .get_mut(pool_id as usize)
+            .unwrap_or_else(|| env::panic_str("Bounty pool not found."));
+        pool.balance.0 += amount;
+        self.ledger.push(LedgerEntry::new(
+            LedgerKind::BountyFunded,
+            &sponsor,
+            None,
+            amount,
+            pool.token.as_ref(),
+        ));
+        pool.history.push(PoolEntry {
+            kind: PoolEntryKind::Deposit,
+            account: sponsor,
+            submission_id: None,
+            amount: U128(amount),
+            timestamp: env::block_timestamp(),
+        });
+        log_str("Bounty pool funded.");
+    }
+
+    // Pays the reviewers of a finalized submission from every bounty pool it matches
+    fn draw_bounties(&mut self, submission_id: u64) {
+        let submission = match self
+            .submissions
+            .iter()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+        {
+            Some(submission) => submission,
+            None => return,
+        };
+        let outcome = match &submission.decision {
+            Some(decision) => decision.outcome.clone(),
+            None => return,
+        };
+        let votes = &submission.submission_votes.revealed_votes;
+        let mut transfers: Vec<(u64, Option<String>, String, Balance)> = Vec::new();
+        for (pool_id, pool) in self.bounty_pools.iter_mut().enumerate() {
+            let matches = (pool.track.is_some() && pool.track == submission.track)
+                || pool
+                    .keywords
+                    .iter()
+                    .any(|keyword| submission.response.contains(keyword.as_str()));
+            if !matches || pool.balance.0 == 0 {
+                continue;
+            }
+            let amount = pool.reward_per_submission.0.min(pool.balance.0);
+            for (reviewer, share) in Self::reward_shares(
+                &self.reward_formula,
+                &submission.suggested_reviewers,
+                votes,
+                &outcome,
+                amount,
+            ) {
+                pool.balance.0 -= share;
+                pool.history.push(PoolEntry {
+                    kind: PoolEntryKind::Payout,
+                    account: reviewer.clone(),
+                    submission_id: Some(submission_id),
+                    amount: U128(share),
+                    timestamp: env::block_timestamp(),
+                });
+                transfers.push((pool_id as u64, pool.token.clone(), reviewer, share));
+            }
+        }
+        for (pool_id, token, reviewer, share) in transfers {
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::RewardPaid,
+                &reviewer,
+                Some(submission_id),
+                share,
+                token.as_ref(),
+            ));
+            Self::pay(token.as_ref(), &reviewer, share).then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_REWARD_CALLBACK)
+                    .on_bounty_transfer(pool_id, submission_id, reviewer, U128(share)),
+            );
+        }
+    }
+
+    // Returns the part of an account's storage balance that cannot be withdrawn
+    fn storage_locked(&self, account: &str) -> Balance {
+        let used = self.storage_used.get(account).copied().unwrap_or(0);
+        STORAGE_BALANCE_MIN + used as u128 * env::storage_byte_cost()
+    }
+
+    // Charges storage used on behalf of an account against its storage balance, if charging is enabled
+    fn charge_storage_to(&mut self, account: &str, bytes: u64) {
+        if !self.charge_storage {
+            return;
+        }
+        let total = self.storage_deposits.get(account).copied().unwrap_or(0);
+        let cost = bytes as u128 * env::storage_byte_cost();
+        if total < self.storage_locked(account) + cost {
+            env::panic_str("Insufficient storage balance. Call storage_deposit first.");
+        }
+        *self.storage_used.entry(account.to_string()).or_insert(0) += bytes;
+    }
+
+    // Writes the state and charges its growth since initial_storage to an account, returning the bytes charged
+    fn charge_storage_since(&mut self, account: &str, initial_storage: u64) -> u64 {
+        if !self.charge_storage {
+            return 0;
+        }
+        env::state_write(self);
+        let bytes = env::storage_usage().saturating_sub(initial_storage);
+        self.charge_storage_to(account, bytes);
+        bytes
+    }
+
+    // Returns whether the signer is the contract owner or the editor of any track
+    fn is_owner_or_editor(&self) -> bool {
+        let signer = env::signer_account_id().to_string();
+        signer == env::current_account_id().to_string()
+            || self
+                .tracks
+                .values()
+                .any(|track| track.editor.as_ref() == Some(&signer))
+    }
+
+    // Returns the appeal of a submission, panicking if either does not exist
+    fn appeal_mut(&mut self, submission_id: u64) -> &mut Appeal {
+        self.submissions
+            .iter_mut()
+            .find(|sub| sub.submission_votes.submission_id == submission_id)
+            .unwrap_or_else(|| env::panic_str("Submission not found."))
+            .appeal
+            .as_mut()
+            .unwrap_or_else(|| env::panic_str("Appeal not found."))
+    }
+
+    // Picks an appeal panel of reviewers who had no part in any earlier round of the submission
+    // Keyword matches come first, then the rest of the pool in registration order
+    fn appeal_panel(&self, submission: &Submission) -> Vec<String> {
+        let mut excluded: Vec<&String> = vec![&submission.author];
+        excluded.extend(&submission.suggested_reviewers);
+        excluded.extend(&submission.replaced_reviewers);
+        excluded.extend(
+            submission
+                .history
+                .iter()
+                .map(|round| &round.submission_votes)
+                .chain(std::iter::once(&submission.submission_votes))
+                .flat_map(|votes| votes.vote_commits.iter().map(|vc| &vc.reviewer)),
+        );
+        let mut panel: Vec<String> = Vec::new();
+        let candidates = self
+            .rank_reviewers(&submission.response)
+            .into_iter()
+            .map(|(name, _)| name)
+            .chain(self.reviewers.iter().map(|r| r.name.clone()));
+        for name in candidates {
+            if panel.len() == APPEAL_PANEL_SIZE {
+                break;
+            }
+            if !excluded.contains(&&name) && !panel.contains(&name) && self.has_min_stake(&name) {
+                panel.push(name);
+            }
+        }
+        panel
+    }
+
+    // Returns the deposit refund policy that applies to a submission
+    fn refund_policy_for(&self, submission: &Submission) -> RefundPolicy {
+        submission
+            .track
+            .as_ref()
+            .and_then(|track| self.tracks.get(track))
+            .and_then(|track| track.refund_policy.clone())
+            .unwrap_or_else(|| self.refund_policy.clone())
+    }
+
+    // Refunds the author's deposit following the refund policy and splits the rest among the panel
+    // Reviewers who revealed share it after a vote; the assigned panel shares it after a desk reject
+    fn settle_deposit(&mut self, submission_id: u64) {
+        let index = match self
+            .submissions
+            .iter()
+            .position(|sub| sub.submission_votes.submission_id == submission_id)
+        {
+            Some(index) => index,
+            None => return,
+        };
+        let submission = &self.submissions[index];
+        if submission.deposit_settled {
+            return;
+        }
+        let policy = self.refund_policy_for(submission);
+        // Spam forfeits go to the treasury; other forfeits are shared by the reviewers who voted
+        let (refund_percent, mut panel): (u8, Vec<String>) =
+            match (&submission.desk_rejection, &submission.decision) {
+                (Some(desk_rejection), _) if desk_rejection.spam => (0, Vec::new()),
+                (Some(_), _) => (
+                    policy.reject_refund_percent,
+                    submission.suggested_reviewers.clone(),
+                ),
+                // The final outcome, after any editor decision or appeal, decides the refund
+                (None, Some(_)) => (
+                    match submission.accepted {
+                        Some(true) => policy.accept_refund_percent,
+                        Some(false) => policy.reject_refund_percent,
+                        None => 100,
+                    },
+                    submission
+                        .submission_votes
+                        .revealed_votes
+                        .keys()
+                        .cloned()
+                        .collect(),
+                ),
+                (None, None) => return,
+            };
+        panel.sort();
+        let deposit = submission.deposit;
+        let forfeited = deposit - deposit * refund_percent as u128 / 100;
+        // Without a panel the treasury keeps the forfeit; rounding leftovers go back to the author
+        let share = if panel.is_empty() {
+            0
+        } else {
+            forfeited / panel.len() as u128
+        };
+        let kept = if panel.is_empty() { forfeited } else { 0 };
+        let refund = deposit - kept - share * panel.len() as u128;
+        let token = submission.fee_token.clone();
+        let author = submission.author.clone();
+        let submission = &mut self.submissions[index];
+        submission.deposit_settled = true;
+        submission.deposit_refunded = refund;
+        submission.deposit_forfeited = deposit - refund;
+        if refund > 0 {
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::Refunded,
+                &author,
+                Some(submission_id),
+                refund,
+                token.as_ref(),
+            ));
+            Self::pay(token.as_ref(), &author, refund);
+        }
+        if kept > 0 {
+            self.slashed_stake += kept;
+            self.ledger.push(LedgerEntry::new(
+                LedgerKind::Slashed,
+                &author,
+                Some(submission_id),
+                kept,
+                token.as_ref(),
+            ));
+        }
+        if share > 0 {
+            for reviewer in panel {
+                self.ledger.push(LedgerEntry::new(
+                    LedgerKind::RewardPaid,
+                    &reviewer,
+                    Some(submission_id),
+                    share,
+                    token.as_ref(),
+                ));
+                Self::pay(token.as_ref(), &reviewer, share).then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_REWARD_CALLBACK)
+                        .on_deposit_transfer(submission_id, reviewer, U128(share)),
+                );
+            }
+        }
+    }
+
+    // Skips from_index entries and returns at most limit of the rest
+    fn paginate(
+        entries: Vec<(usize, &LedgerEntry)>,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<(u64, LedgerEntry)> {
+        entries
+            .into_iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(|(id, entry)| (id as u64, entry.clone()))
+            .collect()
+    }
+
+    // Sums ledger entries per kind, in the order each kind first appears
+    fn ledger_totals(entries: Vec<&LedgerEntry>) -> Vec<(LedgerKind, U128)> {
+        let mut totals: Vec<(LedgerKind, U128)> = Vec::new();
+        for entry in entries {
+            match totals.iter_mut().find(|(kind, _)| *kind == entry.kind) {
+                Some((_, total)) => total.0 += entry.amount.0,
+                None => totals.push((entry.kind.clone(), entry.amount)),
+            }
+        }
+        totals
+    }
+
+    // Returns whether a string is hex-encoded bytes, optionally of an exact byte length
+    fn is_hex(value: &str, bytes: Option<usize>) -> bool {
+        let expected_len = bytes.map(|bytes| 2 * bytes).unwrap_or(value.len());
+        value.len() == expected_len
+            && value.len().is_multiple_of(2)
+            && value.chars().all(|c| c.is_ascii_hexdigit())
+    }
+
+    // Returns the editor responsible for a submission, falling back to its track's editor
+    fn editor_for(&self, submission: &Submission) -> Option<String> {
+        submission.editor.clone().or_else(|| {
+            submission
+                .track
+                .as_ref()
+                .and_then(|track| self.tracks.get(track))
+                .and_then(|track| track.editor.clone())
+        })
+    }
+}
+
+/*
+ * The rest of this file holds the inline tests for the code above
+ * Learn more about Rust tests: https://doc.rust-lang.org/book/ch11-01-writing-tests.html
+ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, VMContext};
+
+    fn get_context(is_owner: bool) -> VMContext {
+        let mut builder = VMContextBuilder::new();
+        if is_owner {
+            builder
+                .current_account_id(accounts(0))
+                .signer_account_id(accounts(0));
+        } else {
+            builder
+                .current_account_id(accounts(0))
+                .signer_account_id(accounts(1));
+        }
+        builder.build()
+    }
+
+    // Switches the signer, keeping the rest of the current context
+    fn set_signer(account: &str) {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(env::current_account_id())
+            .signer_account_id(account.parse().unwrap())
+            .block_timestamp(env::block_timestamp())
+            .account_balance(env::account_balance())
+            .attached_deposit(env::attached_deposit())
+            .storage_usage(env::storage_usage())
+            .build());
+    }
+
+    // Replaces a submission's panel with the given reviewers, bypassing keyword matching
+    fn seat_panel(
+        contract: &mut Contract,
+        submission_id: u64,
+        reviewers: impl IntoIterator<Item = String>,
+    ) {
+        contract.submissions[submission_id as usize].suggested_reviewers =
+            reviewers.into_iter().collect();
+    }
+
+    #[test]
+    fn add_author_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "author.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("snotty-body.testnet".to_string());
+        assert_eq!(contract.authors.len(), 1);
+        assert_eq!(contract.authors[0], "snotty-body.testnet");
+    }
+
+    #[test]
+    fn add_reviewer_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer(
+            "quirky-sand.testnet".to_string(),
+            vec!["rust".to_string(), "smart contract".to_string()],
+        );
+        assert_eq!(contract.reviewers.len(), 1);
+        assert_eq!(contract.reviewers[0].name, "quirky-sand.testnet");
+    }
+
+    // #[test]
+    // fn add_reviewer_only_owner() {
+    //     let context = get_context(false);
+    //     testing_env!(context);
+    //     let mut contract = Contract::new();
+    //     contract.add_reviewer(
+    //         "scandalous-note.testnet".to_string(),
+    //         vec!["blockchain".to_string()],
+    //     );
+    //     assert_eq!(contract.reviewers.len(), 0);
+    // }
+
+    // #[test]
+    // fn add_author_only_owner() {
+    //     let context = get_context(false);
+    //     testing_env!(context);
+    //     let mut contract = Contract::new();
+    //     contract.add_author("dispensable-animal.testnet".to_string());
+    //     assert_eq!(contract.authors.len(), 0);
+    // }
+
+    #[test]
+    fn get_default_license() {
+        let contract = Contract::default();
+        assert_eq!(contract.get_license(), "CC BY-NC-SA".to_string());
+    }
+
+    #[test]
+    fn add_keywords_to_reviewer_success() {
+        let context = get_context(true); // Simulate call by the reviewer themselves
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("dao-guru.testnet".to_string(), vec![]);
+        // Correctly simulate the reviewer adding keywords to themselves
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("dao-guru.testnet".parse().unwrap())
+            .build());
+        contract.add_keywords_to_reviewer(
+            "dao-guru.testnet".to_string(),
+            vec![
+                "governance".to_string(),
+                "voting".to_string(),
+                "consensus".to_string(),
+            ],
+        );
+        let reviewer = contract
+            .reviewers
+            .iter()
+            .find(|r| r.name == "dao-guru.testnet");
+        assert!(
+            reviewer.is_some()
+                && reviewer.unwrap().keywords == vec!["governance", "voting", "consensus"]
+        );
+    }
+
+    #[test]
+    fn add_keywords_to_reviewer_not_found() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_keywords_to_reviewer(
+            "nonexistent-reviewer.testnet".to_string(),
+            vec!["governance".to_string()],
+        );
+        assert!(contract.reviewers.is_empty());
+    }
+
+    #[test]
+    fn add_keywords_to_reviewer_not_self() {
+        let context = get_context(false); // Simulate call by someone other than the reviewer
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("dao-expert.testnet".to_string(), vec![]);
+        contract.add_keywords_to_reviewer(
+            "dao-expert.testnet".to_string(),
+            vec!["decentralization".to_string()],
+        );
+        let reviewer = contract
+            .reviewers
+            .iter()
+            .find(|r| r.name == "dao-expert.testnet");
+        assert!(reviewer.is_none() || reviewer.unwrap().keywords.is_empty()); // Keywords should not be added or reviewer not found
+    }
+
+    #[test]
+    fn endorse_application_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec![]);
+        contract.add_reviewer("reviewer2.testnet".to_string(), vec![]);
+        // Simulate the candidate applying to join the reviewer pool
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("candidate.testnet".parse().unwrap())
+            .build());
+        let application_id =
+            contract.apply_as_reviewer(vec!["zk".to_string()], "PhD in cryptography".to_string());
+        // Existing reviewers endorse the application
+        for i in 1..3 {
+            testing_env!(VMContextBuilder::new()
+                .current_account_id(accounts(0))
+                .signer_account_id(format!("reviewer{}.testnet", i).parse().unwrap())
+                .build());
+            contract.endorse_application(application_id);
+        }
+        assert_eq!(
+            contract.reviewer_applications[0].status,
+            ApplicationStatus::Approved
+        );
+        assert!(contract
+            .reviewers
+            .iter()
+            .any(|r| r.name == "candidate.testnet" && r.keywords == vec!["zk"]));
+        assert!(contract.get_pending_applications().is_empty());
+    }
+
+    #[test]
+    fn endorse_application_not_reviewer() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_required_endorsements(1);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("candidate.testnet".parse().unwrap())
+            .build());
+        let application_id = contract.apply_as_reviewer(vec![], "".to_string());
+        // Someone outside the reviewer pool tries to endorse
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("outsider.testnet".parse().unwrap())
+            .build());
+        contract.endorse_application(application_id);
+        assert!(contract.reviewer_applications[0].endorsements.is_empty());
+        assert!(contract.reviewers.is_empty());
+    }
+
+    #[test]
+    fn endorse_application_already_reviewer() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_required_endorsements(1);
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec![]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("candidate.testnet".parse().unwrap())
+            .build());
+        let application_id = contract.apply_as_reviewer(vec![], "".to_string());
+        // The candidate is added directly while the application is pending
+        contract.add_reviewer("candidate.testnet".to_string(), vec![]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .build());
+        contract.endorse_application(application_id);
+        assert_eq!(
+            contract.reviewer_applications[0].status,
+            ApplicationStatus::Approved
+        );
+        assert_eq!(
+            contract
+                .reviewers
+                .iter()
+                .filter(|r| r.name == "candidate.testnet")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn reject_application_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_required_endorsements(1);
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec![]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("candidate.testnet".parse().unwrap())
+            .build());
+        let application_id = contract.apply_as_reviewer(vec![], "".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .build());
+        contract.reject_application(application_id);
+        assert_eq!(
+            contract.reviewer_applications[0].status,
+            ApplicationStatus::Rejected
+        );
+        assert_eq!(contract.reviewers.len(), 1);
+    }
+
+    #[test]
+    fn test_count_keywords_in_submission() {
+        let contract = Contract::new();
+        let data = "This is a test submission containing keywords such as Rust, Blockchain, and Smart Contract.".to_string();
+        let keywords = vec![
+            "Rust".to_string(),
+            "Blockchain".to_string(),
+            "Smart Contract".to_string(),
+            "Web3".to_string(),
+        ];
+        let count = contract.count_keywords_in_submission(data, keywords);
+        assert_eq!(
+            count, 3,
+            "The count of keywords in the submission should be 3."
+        );
+    }
+
+    #[test]
+    fn test_count_keywords_for_all_reviewers() {
+        let context = get_context(true); // Simulate call by an author
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string()); // Add an author for testing
+                                                           // Simulate the author submitting data
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.add_reviewer(
+            "reviewer1.testnet".to_string(),
+            vec!["rust".to_string(), "smart contract".to_string()],
+        );
+        contract.add_reviewer(
+            "reviewer2.testnet".to_string(),
+            vec!["blockchain".to_string(), "web3".to_string()],
+        );
+        contract.add_reviewer("reviewer3.testnet".to_string(), vec!["rust".to_string()]);
+        contract.add_reviewer(
+            "reviewer4.testnet".to_string(),
+            vec!["smart contract".to_string(), "web3".to_string()],
+        );
+
+        // Verify the number of reviewers added
+        assert_eq!(contract.reviewers.len(), 4, "Should have 4 reviewers added");
+
+        let data = "This submission talks about rust and smart contracts in the context of blockchain and web3.".to_string();
+        // This part of the test remains unchanged as the modification in submit_data method
+        // now automatically handles the recording of suggested reviewers based on the keyword count.
+        // The assertions below ensure that the submit_data method's new behavior is correctly implemented.
+        contract.submit_data(data.clone());
+        assert_eq!(contract.submissions.len(), 1); // Verify submission was added
+        assert_eq!(contract.submissions[0].author, "author.testnet");
+        let submission = contract.submissions.last().unwrap();
+        let suggested_reviewers = &submission.suggested_reviewers;
+
+        assert_eq!(
+            suggested_reviewers.len(),
+            3,
+            "Should have 3 suggested reviewers"
+        );
+        let top_reviewers = contract.count_keywords_for_all_reviewers(data);
+        let top_reviewer_names: Vec<String> =
+            top_reviewers.into_iter().map(|(name, _)| name).collect();
+        assert!(
+            suggested_reviewers
+                .iter()
+                .all(|reviewer| top_reviewer_names.contains(reviewer)),
+            "All suggested reviewers should be among the top reviewers"
+        );
+    }
+
+    #[test]
+    fn count_keywords_uses_keyword_index() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec!["rust".to_string()]);
+        contract.add_reviewer(
+            "reviewer2.testnet".to_string(),
+            vec!["solidity".to_string()],
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer2.testnet".parse().unwrap())
+            .build());
+        contract
+            .add_keywords_to_reviewer("reviewer2.testnet".to_string(), vec!["rust".to_string()]);
+        assert_eq!(
+            contract.keyword_index.get("rust"),
+            Some(&vec![
+                "reviewer1.testnet".to_string(),
+                "reviewer2.testnet".to_string()
+            ])
+        );
+        // Reviewers without a matching keyword are not suggested
+        contract.add_reviewer("reviewer3.testnet".to_string(), vec!["haskell".to_string()]);
+        let mut top_reviewers =
+            contract.count_keywords_for_all_reviewers("A rust contract".to_string());
+        top_reviewers.sort();
+        assert_eq!(
+            top_reviewers,
+            vec![
+                ("reviewer1.testnet".to_string(), 1),
+                ("reviewer2.testnet".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn commit_vote_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "author.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        // Ensure the author is correctly recognized for submission
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission".to_string());
+        seat_panel(&mut contract, 0, ["reviewer.testnet".to_string()]);
+        set_signer("reviewer.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0].submission_votes.vote_commits.len(),
+            1
+        );
+        assert_eq!(
+            contract.submissions[0].submission_votes.vote_commits[0].reviewer,
+            "reviewer.testnet"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Submission not found.")]
+    fn commit_vote_submission_not_found() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        set_signer("reviewer.testnet");
+        contract.commit_vote(
+            1,
+            "reviewer.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+    }
+
+    #[test]
+    fn commit_vote_duplicate_vote() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        contract.submit_data("Test submission".to_string());
+        seat_panel(&mut contract, 0, ["reviewer.testnet".to_string()]);
+        set_signer("reviewer.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        set_signer("reviewer.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer.testnet".to_string(),
+            "reject".to_string(),
+            "secret".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0].submission_votes.vote_commits.len(),
+            1
+        ); // Expecting only one vote commit despite attempting to commit twice
+    }
+
+    #[test]
+    fn commit_vote_for_another_reviewer() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission".to_string());
+        seat_panel(&mut contract, 0, ["reviewer.testnet".to_string()]);
+        set_signer("mallory.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer.testnet".to_string(),
+            "reject".to_string(),
+            "secret".to_string(),
+        );
+        assert!(contract.submissions[0]
+            .submission_votes
+            .vote_commits
+            .is_empty());
+    }
+
+    #[test]
+    fn replace_stale_reviewer_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        for i in 1..5 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
+        contract.submit_data("A paper about rust".to_string());
+        let stale = contract.submissions[0].suggested_reviewers[0].clone();
+        // Simulate the commit window passing without the reviewer committing
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(1))
+            .block_timestamp(DEFAULT_COMMIT_WINDOW + 1)
+            .build());
+        contract.replace_stale_reviewer(0, stale.clone());
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.suggested_reviewers.len(), 3);
+        assert!(!submission.suggested_reviewers.contains(&stale));
+        // Ties are broken the same way as the initial panel, so reviewer1 is next in line
+        assert!(submission
+            .suggested_reviewers
+            .contains(&"reviewer1.testnet".to_string()));
+        assert_eq!(
+            submission.commit_deadlines.get("reviewer1.testnet"),
+            Some(&(2 * DEFAULT_COMMIT_WINDOW + 1))
+        );
+        assert_eq!(submission.replaced_reviewers, vec![stale.clone()]);
+        let profile = contract.reviewers.iter().find(|r| r.name == stale).unwrap();
+        assert_eq!(profile.no_shows, 1);
+        // Neither the replaced reviewer nor an outsider can commit a vote
+        set_signer(&stale);
+        contract.commit_vote(0, stale, "accept".to_string(), "secret".to_string());
+        set_signer("outsider.testnet");
+        contract.commit_vote(
+            0,
+            "outsider.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        assert!(contract.submissions[0]
+            .submission_votes
+            .vote_commits
+            .is_empty());
+    }
+
+    #[test]
+    fn replace_stale_reviewer_before_deadline() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        for i in 1..5 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
+        contract.submit_data("A paper about rust".to_string());
+        let reviewer = contract.submissions[0].suggested_reviewers[0].clone();
+        contract.replace_stale_reviewer(0, reviewer.clone());
+        assert!(contract.submissions[0]
+            .suggested_reviewers
+            .contains(&reviewer));
+        assert!(contract.submissions[0].replaced_reviewers.is_empty());
+    }
+
+    #[test]
+    fn end_voting_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "author.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for voting".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        // Simulate three reviewers committing their votes
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        // Note: Direct log assertion is not supported with the current testing utilities
+        // The test will focus on the behavior that can be verified
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.end_voting(0);
+        assert!(
+            contract.submissions[0].voting_ended,
+            "Voting should be marked as ended."
+        );
+    }
+
+    #[test]
+    fn reveal_vote_invalid_value() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission with a typo".to_string());
+        seat_panel(&mut contract, 0, ["reviewer0.testnet".to_string()]);
+        set_signer("reviewer0.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer0.testnet".to_string(),
+            "acept".to_string(),
+            "secret".to_string(),
+        );
+        contract.end_voting(0);
+        contract.reveal_vote(
+            0,
+            "reviewer0.testnet".to_string(),
+            "acept".to_string(),
+            "secret".to_string(),
+        );
+        // A misspelled vote is not counted as a reject
+        assert!(contract.submissions[0]
+            .submission_votes
+            .revealed_votes
+            .is_empty());
+    }
+
+    #[test]
+    fn small_panel_reaches_a_decision() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_decision_rule(DecisionRule {
+            quorum: 1,
+            ..Default::default()
+        });
+        // Only two reviewers match, so the panel has two members
+        for i in 1..3 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
+        contract.submit_data("A paper about rust".to_string());
+        assert_eq!(contract.submissions[0].suggested_reviewers.len(), 2);
+        set_signer("reviewer1.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer1.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        // reviewer2 still has time to commit
+        contract.end_voting(0);
+        assert!(!contract.submissions[0].voting_ended);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMIT_WINDOW + 1)
+            .build());
+        contract.end_voting(0);
+        assert!(contract.submissions[0].voting_ended);
+        contract.reveal_vote(
+            0,
+            "reviewer1.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(true));
+    }
+
+    #[test]
+    fn reveal_vote_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for reveal".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (1..4).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        set_signer("reviewer1.testnet");
+        contract.commit_vote(
+            0,
+            "reviewer1.testnet".to_string(),
+            "accept".to_string(),
+            "secret123".to_string(),
+        );
+        // Simulate three reviewers committing their votes
+        for i in 1..4 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret123".to_string(),
+            );
+        }
+        // End voting after all reviewers have committed their votes
+        contract.end_voting(0);
+        assert!(
+            contract.submissions[0].voting_ended,
+            "Voting should be marked as ended."
+        );
+        // Now attempt to reveal a vote
+        contract.reveal_vote(
+            0,
+            "reviewer1.testnet".to_string(),
+            "accept".to_string(),
+            "secret123".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0]
+                .submission_votes
+                .revealed_votes
+                .get("reviewer1.testnet"),
+            Some(&"accept".to_string()),
+            "Vote should be revealed successfully."
+        );
+    }
+
+    #[test]
+    fn reveal_vote_after_reveal_deadline() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission with a late reveal".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        assert_eq!(
+            contract.submissions[0].reveal_deadline,
+            Some(DEFAULT_REVEAL_WINDOW)
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_REVEAL_WINDOW + 1)
+            .build());
+        contract.reveal_vote(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        assert!(contract.submissions[0]
+            .submission_votes
+            .revealed_votes
+            .is_empty());
+    }
+
+    #[test]
+    fn forfeit_unrevealed_votes_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        for i in 0..3 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec![]);
+        }
+        contract.submit_data("Test submission with a silent reviewer".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        for i in 0..2 {
+            contract.reveal_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        // Forfeiting is not possible while reviewers can still reveal
+        contract.forfeit_unrevealed_votes(0);
+        assert!(contract.submissions[0]
+            .submission_votes
+            .forfeited_reviewers
+            .is_empty());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(1))
+            .block_timestamp(DEFAULT_REVEAL_WINDOW + 1)
+            .build());
+        contract.forfeit_unrevealed_votes(0);
+        contract.forfeit_unrevealed_votes(0);
+        assert_eq!(
+            contract.submissions[0].submission_votes.forfeited_reviewers,
+            vec!["reviewer2.testnet".to_string()]
+        );
+        assert_eq!(contract.reviewers[2].unrevealed_commits, 1);
+        assert_eq!(contract.reviewers[0].unrevealed_commits, 0);
+    }
+
+    #[test]
+    fn commit_comment_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for comment".to_string());
+        seat_panel(&mut contract, 0, ["reviewer1.testnet".to_string()]);
+        set_signer("reviewer1.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer1.testnet".to_string(),
+            "Great proposal".to_string(),
+            "secret123".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0]
+                .submission_votes
+                .comment_commits
+                .len(),
+            1,
+            "Comment should be committed successfully."
+        );
+    }
+
+    #[test]
+    fn reveal_comment_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_author("author.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for reveal comment".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (1..4).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        set_signer("reviewer1.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer1.testnet".to_string(),
+            "Insightful analysis".to_string(),
+            "secret123".to_string(),
+        );
+        // Simulate three reviewers committing their votes
+        for i in 1..4 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret123".to_string(),
+            );
+        }
+        // End voting after all reviewers have committed their votes
+        contract.end_voting(0);
+        assert!(
+            contract.submissions[0].voting_ended,
+            "Voting should be marked as ended."
+        );
+        // Now attempt to reveal a comment once the comment commit window has closed
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        contract.reveal_comment(
+            0,
+            "reviewer1.testnet".to_string(),
+            "Insightful analysis".to_string(),
+            "secret123".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0].submission_votes.revealed_comments,
+            vec![Comment {
+                comment_id: 0,
+                commenter: "reviewer1.testnet".to_string(),
+                text: "Insightful analysis".to_string(),
+                parent_id: None,
+            }],
+            "Comment should be revealed successfully."
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the reviewer can commit their own comment.")]
+    fn commit_comment_by_another_account() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission for comment".to_string());
+        seat_panel(&mut contract, 0, ["reviewer1.testnet".to_string()]);
+        set_signer("mallory.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer1.testnet".to_string(),
+            "Reject this paper".to_string(),
+            "secret".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only assigned reviewers can comment on a submission.")]
+    fn commit_comment_off_panel() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission for comment".to_string());
+        seat_panel(&mut contract, 0, ["reviewer1.testnet".to_string()]);
+        set_signer("reviewer2.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer2.testnet".to_string(),
+            "Reject this paper".to_string(),
+            "secret".to_string(),
+        );
+    }
+
+    #[test]
+    fn reveal_multiple_comments_and_replies() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission for threaded comments".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..2).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        set_signer("reviewer0.testnet");
+        let first = contract.commit_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The proof of lemma 2 is incomplete".to_string(),
+            "secret".to_string(),
+        );
+        let second = contract.commit_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "Figure 3 is unreadable".to_string(),
+            "secret".to_string(),
+        );
+        set_signer("reviewer1.testnet");
+        let reply = contract.commit_reply(
+            0,
+            "reviewer1.testnet".to_string(),
+            first,
+            "Agreed, the induction step is missing".to_string(),
+            "secret".to_string(),
+        );
+        assert_eq!((first, second, reply), (0, 1, 2));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        // Reveals are matched by hash, so the second comment can be revealed first
+        contract.reveal_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "Figure 3 is unreadable".to_string(),
+            "secret".to_string(),
+        );
+        contract.reveal_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The proof of lemma 2 is incomplete".to_string(),
+            "secret".to_string(),
+        );
+        contract.reveal_comment(
+            0,
+            "reviewer1.testnet".to_string(),
+            "Agreed, the induction step is missing".to_string(),
+            "secret".to_string(),
+        );
+        let comments = contract.get_comments(0);
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].comment_id, 1);
+        assert_eq!(comments[1].comment_id, 0);
+        assert_eq!(comments[2].parent_id, Some(0));
+    }
+
+    #[test]
+    fn comments_without_votes_and_public_comments() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission with a comment-only reviewer".to_string());
+        seat_panel(&mut contract, 0, ["reviewer9.testnet".to_string()]);
+        set_signer("reviewer9.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer9.testnet".to_string(),
+            "Related work misses two key papers".to_string(),
+            "secret".to_string(),
+        );
+        // Public comments wait for a decision
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reader.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        contract.reveal_comment(
+            0,
+            "reviewer9.testnet".to_string(),
+            "Related work misses two key papers".to_string(),
+            "secret".to_string(),
+        );
+        assert_eq!(contract.get_comments(0).len(), 1);
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        let comment_id =
+            contract.post_public_comment(0, "Congratulations on the result".to_string(), Some(0));
+        assert_eq!(comment_id, 1);
+        let public_comments = contract.get_public_comments(0);
+        assert_eq!(public_comments[0].commenter, "reader.testnet");
+        assert_eq!(public_comments[0].parent_id, Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Public comments open once a decision exists.")]
+    fn post_public_comment_before_decision() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Undecided submission".to_string());
+        contract.post_public_comment(0, "Looks interesting".to_string(), None);
+    }
+
+    #[test]
+    fn post_encrypted_comment_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer0.testnet".to_string(), vec!["rust".to_string()]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        // Keys that are not 32 bytes of hex are ignored
+        contract.register_public_key("not-a-key".to_string());
+        assert_eq!(contract.get_public_key("author.testnet".to_string()), None);
+        contract.register_public_key("AB".repeat(32));
+        contract.submit_data("A paper about rust".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .build());
+        let comment_id =
+            contract.post_encrypted_comment(0, "deadbeef".to_string(), "00".repeat(24));
+        assert_eq!(comment_id, 0);
+        let comments = contract.get_encrypted_comments(0);
+        assert_eq!(comments[0].reviewer, "reviewer0.testnet");
+        assert_eq!(comments[0].author_key, "ab".repeat(32));
+        assert_eq!(comments[0].ciphertext, "deadbeef");
+    }
+
+    #[test]
+    #[should_panic(expected = "Nonce must be 24 bytes in hex.")]
+    fn post_encrypted_comment_bad_nonce() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer0.testnet".to_string(), vec!["rust".to_string()]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.register_public_key("ab".repeat(32));
+        contract.submit_data("A paper about rust".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .build());
+        contract.post_encrypted_comment(0, "deadbeef".to_string(), "00".repeat(12));
+    }
+
+    #[test]
+    fn annotate_and_get_annotations_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer0.testnet".to_string(), vec!["rust".to_string()]);
+        contract.submit_data("A paper about rust, ünïcode".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .build());
+        contract.annotate(0, None, 14, 18, "Which edition?".to_string());
+        contract.annotate(0, None, 0, 7, "Title is vague.".to_string());
+        // Offsets count characters, so the last character is in bounds
+        contract.annotate(0, Some(1), 20, 27, "Typo.".to_string());
+        let annotations = contract.get_annotations(0, None);
+        assert_eq!(annotations.len(), 3);
+        assert_eq!(annotations[0].text, "Title is vague.");
+        assert_eq!(annotations[1].annotation_id, 0);
+        assert_eq!(annotations[2].end, 27);
+    }
+
+    #[test]
+    #[should_panic(expected = "Annotation range is out of bounds.")]
+    fn annotate_out_of_bounds() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer0.testnet".to_string(), vec!["rust".to_string()]);
+        contract.submit_data("A paper about rust".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .build());
+        contract.annotate(0, None, 10, 19, "Past the end.".to_string());
+    }
+
+    #[test]
+    fn rebuttal_and_vote_update_success() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for rebuttal".to_string());
+        seat_panel(&mut contract, 0, ["reviewer0.testnet".to_string()]);
+        set_signer("reviewer0.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "accept", "accept"]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        contract.reveal_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        contract.open_rebuttal(0);
+        contract.post_rebuttal(
+            0,
+            0,
+            "We added a stronger baseline in the appendix".to_string(),
+        );
+        set_signer("reviewer0.testnet");
+        contract.commit_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        // Only reviewers whose vote was revealed can update it
+        set_signer("reviewer9.testnet");
+        contract.commit_vote_update(
+            0,
+            "reviewer9.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0]
+                .submission_votes
+                .vote_update_commits
+                .len(),
+            1
+        );
+        // Finalization waits for the rebuttal window to close
+        contract.finalize_submission(0);
+        assert!(contract.submissions[0].decision.is_none());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + DEFAULT_REBUTTAL_WINDOW + 2)
+            .build());
+        // ... and for committed updates to be revealed
+        contract.finalize_submission(0);
+        assert!(contract.submissions[0].decision.is_none());
+        set_signer("reviewer0.testnet");
+        contract.reveal_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        let votes = &contract.submissions[0].submission_votes;
+        assert_eq!(
+            votes.rebuttals.get(&0),
+            Some(&"We added a stronger baseline in the appendix".to_string())
+        );
+        assert_eq!(
+            votes.revealed_votes.get("reviewer0.testnet"),
+            Some(&"accept".to_string())
+        );
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(true));
+    }
+
+    #[test]
+    fn vote_update_clears_stale_confidence_and_prediction() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission for rebuttal".to_string());
+        seat_panel(&mut contract, 0, ["reviewer0.testnet".to_string()]);
+        set_signer("reviewer0.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        let ballot = Ballot {
+            vote: "reject".to_string(),
+            confidence: Some(5),
+            prediction: Some(30),
+            ..Default::default()
+        };
+        contract.commit_ballot(
+            0,
+            "reviewer0.testnet".to_string(),
+            ballot.clone(),
+            "secret".to_string(),
+        );
+        contract.end_voting(0);
+        contract.reveal_ballot(
+            0,
+            "reviewer0.testnet".to_string(),
+            ballot,
+            "secret".to_string(),
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        contract.reveal_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        contract.open_rebuttal(0);
+        set_signer("reviewer0.testnet");
+        contract.commit_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + DEFAULT_REBUTTAL_WINDOW + 2)
+            .build());
+        contract.reveal_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        let votes = &contract.submissions[0].submission_votes;
+        assert_eq!(
+            votes.revealed_votes.get("reviewer0.testnet"),
+            Some(&"accept".to_string())
+        );
+        // The old confidence and prediction described the reject vote
+        assert!(!votes.revealed_confidences.contains_key("reviewer0.testnet"));
+        assert!(!votes.revealed_predictions.contains_key("reviewer0.testnet"));
+    }
+
+    #[test]
+    fn vote_update_by_another_account() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission for rebuttal".to_string());
+        seat_panel(&mut contract, 0, ["reviewer0.testnet".to_string()]);
+        set_signer("reviewer0.testnet");
+        contract.commit_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_COMMENT_COMMIT_WINDOW + 1)
+            .build());
+        contract.reveal_comment(
+            0,
+            "reviewer0.testnet".to_string(),
+            "The baseline is too weak".to_string(),
+            "secret".to_string(),
+        );
+        contract.open_rebuttal(0);
+        // Another account cannot commit an update on the reviewer's behalf
+        set_signer("mallory.testnet");
+        contract.commit_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "reject".to_string(),
+            "secret2".to_string(),
+        );
+        assert!(contract.submissions[0]
+            .submission_votes
+            .vote_update_commits
+            .is_empty());
+        // ... so the reviewer can still commit their own
+        set_signer("reviewer0.testnet");
+        contract.commit_vote_update(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret2".to_string(),
+        );
+        assert_eq!(
+            contract.submissions[0]
+                .submission_votes
+                .vote_update_commits
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn finalize_submission_success() {
+        let mut context = get_context(true);
+        context.signer_account_id = "author.testnet".parse().unwrap();
+        testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_reviewer(
-            "quirky-sand.testnet".to_string(),
-            vec!["rust".to_string(), "smart contract".to_string()],
+        contract.add_author("author.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("Test submission for finalization".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        // Simulate three reviewers committing their votes
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        // Votes can only be revealed once voting has ended
+        for i in 0..3 {
+            contract.reveal_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.finalize_submission(0);
+        assert_eq!(
+            contract.submissions[0].accepted,
+            Some(true),
+            "Submission should be accepted."
         );
-        assert_eq!(contract.reviewers.len(), 1);
-        assert_eq!(contract.reviewers[0].name, "quirky-sand.testnet");
     }
 
-    // #[test]
-    // fn add_reviewer_only_owner() {
-    //     let context = get_context(false);
-    //     testing_env!(context);
-    //     let mut contract = Contract::new();
-    //     contract.add_reviewer(
-    //         "scandalous-note.testnet".to_string(),
-    //         vec!["blockchain".to_string()],
-    //     );
-    //     assert_eq!(contract.reviewers.len(), 0);
-    // }
+    // Commits and reveals one vote per entry, ending voting in between
+    fn commit_and_reveal_votes(contract: &mut Contract, submission_id: u64, votes: &[&str]) {
+        seat_panel(
+            contract,
+            submission_id,
+            (0..votes.len()).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        let signer = env::signer_account_id();
+        for (i, vote) in votes.iter().enumerate() {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                submission_id,
+                format!("reviewer{}.testnet", i),
+                vote.to_string(),
+                "secret".to_string(),
+            );
+        }
+        set_signer(signer.as_str());
+        contract.end_voting(submission_id);
+        for (i, vote) in votes.iter().enumerate() {
+            contract.reveal_vote(
+                submission_id,
+                format!("reviewer{}.testnet", i),
+                vote.to_string(),
+                "secret".to_string(),
+            );
+        }
+    }
 
-    // #[test]
-    // fn add_author_only_owner() {
-    //     let context = get_context(false);
-    //     testing_env!(context);
-    //     let mut contract = Contract::new();
-    //     contract.add_author("dispensable-animal.testnet".to_string());
-    //     assert_eq!(contract.authors.len(), 0);
-    // }
+    #[test]
+    fn finalize_submission_no_quorum() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission without reveals".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_vote(
+                0,
+                format!("reviewer{}.testnet", i),
+                "accept".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        contract.reveal_vote(
+            0,
+            "reviewer0.testnet".to_string(),
+            "accept".to_string(),
+            "secret".to_string(),
+        );
+        // Finalization waits for the other reviewers until the reveal window closes
+        contract.finalize_submission(0);
+        assert!(contract.get_decision(0).is_none());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_REVEAL_WINDOW + 1)
+            .build());
+        contract.finalize_submission(0);
+        // A single reveal is below the default quorum, so nothing is accepted
+        assert_eq!(contract.submissions[0].accepted, None);
+        let decision = contract.get_decision(0).unwrap();
+        assert_eq!(decision.outcome, DecisionOutcome::NoQuorum);
+        assert_eq!(decision.abstentions, 2);
+    }
 
     #[test]
-    fn get_default_license() {
-        let contract = Contract::default();
-        assert_eq!(contract.get_license(), "CC BY-NC-SA".to_string());
+    fn set_decision_rule_rejects_zero_quorum() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_decision_rule(DecisionRule {
+            threshold: VoteThreshold::SimpleMajority,
+            quorum: 0,
+            weight_by_confidence: false,
+        });
+        assert_eq!(contract.decision_rule, DecisionRule::default());
+    }
+
+    #[test]
+    fn finalize_submission_simple_majority() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_decision_rule(DecisionRule {
+            threshold: VoteThreshold::SimpleMajority,
+            quorum: 2,
+            weight_by_confidence: false,
+        });
+        contract.submit_data("Test submission for majority".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "reject"]);
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(true));
+        let decision = contract.get_decision(0).unwrap();
+        assert_eq!(decision.rule.threshold, VoteThreshold::SimpleMajority);
+        assert_eq!((decision.accept_votes, decision.reject_votes), (2, 1));
+    }
+
+    #[test]
+    fn finalize_submission_track_supermajority() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_track("cryptography".to_string());
+        contract.set_track_decision_rule(
+            "cryptography".to_string(),
+            Some(DecisionRule {
+                threshold: VoteThreshold::Supermajority {
+                    numerator: 3,
+                    denominator: 4,
+                },
+                quorum: 1,
+                weight_by_confidence: false,
+            }),
+        );
+        contract.submit_to_track("cryptography".to_string(), "Track submission".to_string());
+        contract.submit_data("Default rule submission".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "reject"]);
+        commit_and_reveal_votes(&mut contract, 1, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        contract.finalize_submission(1);
+        // Two out of three is below the track's three-quarter threshold
+        assert_eq!(contract.submissions[0].accepted, Some(false));
+        assert_eq!(
+            contract.get_decision(0).unwrap().rule.threshold,
+            VoteThreshold::Supermajority {
+                numerator: 3,
+                denominator: 4
+            }
+        );
+        // Submissions outside the track use the contract-wide unanimity rule
+        assert_eq!(contract.submissions[1].accepted, Some(true));
+        assert_eq!(
+            contract.get_decision(1).unwrap().rule,
+            DecisionRule::default()
+        );
+    }
+
+    #[test]
+    fn finalize_submission_confidence_weighted() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_decision_rule(DecisionRule {
+            threshold: VoteThreshold::SimpleMajority,
+            quorum: 2,
+            weight_by_confidence: true,
+        });
+        contract.submit_data("Test submission with confidence".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        let ballots = [("accept", 5), ("reject", 1), ("reject", 2)];
+        for (i, (vote, confidence)) in ballots.iter().enumerate() {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: vote.to_string(),
+                    confidence: Some(*confidence),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        for (i, (vote, confidence)) in ballots.iter().enumerate() {
+            contract.reveal_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: vote.to_string(),
+                    confidence: Some(*confidence),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.finalize_submission(0);
+        // One confident accept outweighs two unsure rejects
+        assert_eq!(contract.submissions[0].accepted, Some(true));
+        let decision = contract.get_decision(0).unwrap();
+        assert_eq!((decision.accept_weight, decision.reject_weight), (5, 3));
+        assert_eq!(decision.vote_weights.get("reviewer1.testnet"), Some(&1));
+        assert_eq!((decision.accept_votes, decision.reject_votes), (1, 2));
+    }
+
+    #[test]
+    fn finalize_submission_truthfulness_scores() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        // Scores are recorded even when the quorum is not met
+        contract.set_decision_rule(DecisionRule {
+            quorum: 4,
+            ..Default::default()
+        });
+        for i in 0..3 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec![]);
+        }
+        contract.submit_data("Test submission with predictions".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        let ballots = [("accept", 40), ("accept", 40), ("reject", 80)];
+        for (i, (vote, prediction)) in ballots.iter().enumerate() {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: vote.to_string(),
+                    prediction: Some(*prediction),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        for (i, (vote, prediction)) in ballots.iter().enumerate() {
+            contract.reveal_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: vote.to_string(),
+                    prediction: Some(*prediction),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, None);
+        // Finalizing again does not score the reviewers twice
+        contract.finalize_submission(0);
+        let scores = &contract.submissions[0].submission_votes.truthfulness_scores;
+        // Accept turned out more common than the panel predicted, so accept voters score higher
+        assert!(scores["reviewer0.testnet"] > scores["reviewer2.testnet"]);
+        assert_eq!(scores["reviewer0.testnet"], scores["reviewer1.testnet"]);
+        let profile = &contract.reviewers[0];
+        assert_eq!(profile.truthfulness_score, scores["reviewer0.testnet"]);
+        assert_eq!(profile.scored_reviews, 1);
+    }
+
+    #[test]
+    fn finalize_submission_pays_reviewers() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(1_000), false);
+        contract.set_reward_formula(RewardFormula::OutcomeAlignment);
+        contract.set_decision_rule(DecisionRule {
+            threshold: VoteThreshold::SimpleMajority,
+            quorum: 2,
+            weight_by_confidence: false,
+        });
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.submit_data("Test submission with a review fee".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "reject"]);
+        contract.finalize_submission(0);
+        let payouts = &contract.submissions[0].reward_payouts;
+        // Aligned reviewers get twice the share of the dissenting reviewer
+        assert_eq!(payouts["reviewer0.testnet"], 400);
+        assert_eq!(payouts["reviewer1.testnet"], 400);
+        assert_eq!(payouts["reviewer2.testnet"], 200);
+        assert_eq!(contract.submissions[0].reward_refunded, 0);
+    }
+
+    #[test]
+    fn reward_shares_only_pay_the_panel() {
+        let panel = vec![
+            "reviewer0.testnet".to_string(),
+            "reviewer1.testnet".to_string(),
+        ];
+        let votes: HashMap<String, String> =
+            ["reviewer0.testnet", "reviewer1.testnet", "mallory.testnet"]
+                .iter()
+                .map(|name| (name.to_string(), "accept".to_string()))
+                .collect();
+        let shares = Contract::reward_shares(
+            &RewardFormula::EqualSplit,
+            &panel,
+            &votes,
+            &DecisionOutcome::Accepted,
+            900,
+        );
+        assert_eq!(
+            shares,
+            vec![
+                ("reviewer0.testnet".to_string(), 450),
+                ("reviewer1.testnet".to_string(), 450)
+            ]
+        );
+    }
+
+    #[test]
+    fn on_reward_transfer_failure_refunds_author() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission with a failed payout".to_string());
+        contract.submissions[0]
+            .reward_payouts
+            .insert("reviewer0.testnet".to_string(), 500);
+        contract.on_reward_transfer(
+            0,
+            "reviewer0.testnet".to_string(),
+            U128(500),
+            Err(PromiseError::Failed),
+        );
+        assert!(contract.submissions[0].reward_payouts.is_empty());
+        assert_eq!(contract.submissions[0].reward_refunded, 500);
+    }
+
+    #[test]
+    fn finalize_submission_draws_bounty_pool() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("sponsor.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        let pool_id = contract.create_bounty_pool(vec!["rust".to_string()], None, U128(600));
+        contract.create_bounty_pool(vec!["haskell".to_string()], None, U128(600));
+        contract.submit_data("A paper about rust".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        let pool = contract.get_bounty_pool(pool_id).unwrap();
+        assert_eq!(pool.balance, U128(400));
+        assert_eq!(pool.history.len(), 4);
+        assert_eq!(pool.history[1].kind, PoolEntryKind::Payout);
+        assert_eq!(pool.history[1].amount, U128(200));
+        // The second pool does not match the submission and keeps its balance
+        assert_eq!(contract.get_bounty_pool(1).unwrap().balance, U128(1_000));
+    }
+
+    #[test]
+    fn ft_on_transfer_submit_and_fund_pool() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        let token: AccountId = "token.testnet".parse().unwrap();
+        contract.set_accepted_token(token.clone(), Some(U128(100)));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .predecessor_account_id(token.clone())
+            .build());
+        let unused = contract.ft_on_transfer(
+            "author.testnet".parse().unwrap(),
+            U128(150),
+            r#"{"action": "submit", "data": "A paper about rust"}"#.to_string(),
+        );
+        assert!(matches!(unused, PromiseOrValue::Value(U128(50))));
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.author, "author.testnet");
+        assert_eq!(submission.review_fee, 100);
+        assert_eq!(submission.fee_token, Some("token.testnet".to_string()));
+        contract.ft_on_transfer(
+            "sponsor.testnet".parse().unwrap(),
+            U128(1_000),
+            r#"{"action": "create_pool", "keywords": ["rust"], "reward_per_submission": "300"}"#
+                .to_string(),
+        );
+        contract.ft_on_transfer(
+            "sponsor.testnet".parse().unwrap(),
+            U128(500),
+            r#"{"action": "fund_pool", "pool_id": 0}"#.to_string(),
+        );
+        let pool = contract.get_bounty_pool(0).unwrap();
+        assert_eq!(pool.token, Some("token.testnet".to_string()));
+        assert_eq!(pool.balance, U128(1_500));
+        assert_eq!(pool.history.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is not accepted.")]
+    fn ft_on_transfer_unknown_token() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .predecessor_account_id("token.testnet".parse().unwrap())
+            .build());
+        contract.ft_on_transfer(
+            "author.testnet".parse().unwrap(),
+            U128(150),
+            r#"{"action": "submit", "data": "A paper about rust"}"#.to_string(),
+        );
+    }
+
+    #[test]
+    fn finalize_submission_refunds_deposit_by_track_policy() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_track("cryptography".to_string());
+        contract.set_submission_deposit(U128(1_000));
+        contract.set_track_refund_policy(
+            "cryptography".to_string(),
+            Some(RefundPolicy {
+                accept_refund_percent: 100,
+                reject_refund_percent: 40,
+            }),
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.submit_to_track("cryptography".to_string(), "Track submission".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "accept"]);
+        contract.finalize_submission(0);
+        // A rejection can still be appealed, so the deposit waits for the editor window
+        contract.settle_submission_deposit(0);
+        assert!(!contract.submissions[0].deposit_settled);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(1))
+            .block_timestamp(DEFAULT_EDITOR_WINDOW + 1)
+            .build());
+        contract.settle_submission_deposit(0);
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.accepted, Some(false));
+        assert_eq!(submission.deposit, 1_000);
+        assert_eq!(submission.deposit_refunded, 400);
+        assert_eq!(submission.deposit_forfeited, 600);
     }
 
     #[test]
-    fn add_keywords_to_reviewer_success() {
-        let context = get_context(true); // Simulate call by the reviewer themselves
+    fn editor_acceptance_refunds_deposit() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_reviewer("dao-guru.testnet".to_string(), vec![]);
-        // Correctly simulate the reviewer adding keywords to themselves
+        contract.add_track("economics".to_string());
+        contract.set_track_editor("economics".to_string(), Some("editor.testnet".to_string()));
+        contract.set_submission_deposit(U128(1_000));
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
-            .signer_account_id("dao-guru.testnet".parse().unwrap())
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
             .build());
-        contract.add_keywords_to_reviewer(
-            "dao-guru.testnet".to_string(),
-            vec![
-                "governance".to_string(),
-                "voting".to_string(),
-                "consensus".to_string(),
-            ],
-        );
-        let reviewer = contract
-            .reviewers
-            .iter()
-            .find(|r| r.name == "dao-guru.testnet");
-        assert!(
-            reviewer.is_some()
-                && reviewer.unwrap().keywords == vec!["governance", "voting", "consensus"]
+        contract.submit_to_track("economics".to_string(), "Test submission".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "reject"]);
+        contract.finalize_submission(0);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("editor.testnet".parse().unwrap())
+            .build());
+        contract.issue_editor_decision(
+            0,
+            EditorVerdict::Accept,
+            "The reviewers missed the main contribution.".to_string(),
         );
+        // The deposit is settled on the editor's acceptance, not the mechanical rejection
+        let submission = &contract.submissions[0];
+        assert!(submission.deposit_settled);
+        assert_eq!(submission.deposit_refunded, 1_000);
+        assert_eq!(submission.deposit_forfeited, 0);
     }
 
     #[test]
-    fn add_keywords_to_reviewer_not_found() {
+    fn desk_reject_spam_forfeits_deposit() {
         let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_keywords_to_reviewer(
-            "nonexistent-reviewer.testnet".to_string(),
-            vec!["governance".to_string()],
-        );
-        assert!(contract.reviewers.is_empty());
+        for i in 0..3 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
+        contract.set_submission_deposit(U128(900));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(900)
+            .build());
+        contract.submit_data("Buy cheap rust tokens now".to_string());
+        contract.submissions[0].editor = Some("editor.testnet".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("editor.testnet".parse().unwrap())
+            .build());
+        contract.desk_reject(0, "Advertisement, not a paper.".to_string(), true);
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.accepted, Some(false));
+        assert!(submission.desk_rejection.as_ref().unwrap().spam);
+        assert_eq!(submission.deposit_refunded, 0);
+        assert_eq!(submission.deposit_forfeited, 900);
+        // The panel did not review spam, so the treasury keeps the deposit
+        assert_eq!(contract.get_treasury_report().slashed, U128(900));
+        assert!(contract
+            .get_account_ledger("reviewer0.testnet".to_string(), 0, 10)
+            .is_empty());
     }
 
     #[test]
-    fn add_keywords_to_reviewer_not_self() {
-        let context = get_context(false); // Simulate call by someone other than the reviewer
+    fn desk_reject_without_panel_keeps_forfeit() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_reviewer("dao-expert.testnet".to_string(), vec![]);
-        contract.add_keywords_to_reviewer(
-            "dao-expert.testnet".to_string(),
-            vec!["decentralization".to_string()],
-        );
-        let reviewer = contract
-            .reviewers
-            .iter()
-            .find(|r| r.name == "dao-expert.testnet");
-        assert!(reviewer.is_none() || reviewer.unwrap().keywords.is_empty()); // Keywords should not be added or reviewer not found
+        contract.set_submission_deposit(U128(1_000));
+        contract.set_refund_policy(RefundPolicy {
+            accept_refund_percent: 100,
+            reject_refund_percent: 40,
+        });
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.submit_data("A paper nobody can review".to_string());
+        contract.submissions[0].editor = Some("editor.testnet".to_string());
+        set_signer("editor.testnet");
+        contract.desk_reject(0, "Out of scope.".to_string(), false);
+        // Nobody was assigned, so the forfeit is kept instead of refunded
+        let submission = &contract.submissions[0];
+        assert!(submission.suggested_reviewers.is_empty());
+        assert_eq!(submission.deposit_refunded, 400);
+        assert_eq!(submission.deposit_forfeited, 600);
+        assert_eq!(contract.get_treasury_report().slashed, U128(600));
     }
 
     #[test]
-    fn test_count_keywords_in_submission() {
-        let contract = Contract::new();
-        let data = "This is a test submission containing keywords such as Rust, Blockchain, and Smart Contract.".to_string();
-        let keywords = vec![
-            "Rust".to_string(),
-            "Blockchain".to_string(),
-            "Smart Contract".to_string(),
-            "Web3".to_string(),
-        ];
-        let count = contract.count_keywords_in_submission(data, keywords);
-        assert_eq!(
-            count, 3,
-            "The count of keywords in the submission should be 3."
+    fn on_deposit_transfer_failure_keeps_share() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Test submission with a failed payout".to_string());
+        contract.on_deposit_transfer(
+            0,
+            "reviewer0.testnet".to_string(),
+            U128(300),
+            Err(PromiseError::Failed),
         );
+        assert_eq!(contract.get_treasury_report().slashed, U128(300));
+        let ledger = contract.get_submission_ledger(0, 0, 10);
+        assert_eq!(ledger[0].1.kind, LedgerKind::RewardReturned);
+        assert_eq!(ledger[1].1.kind, LedgerKind::Slashed);
     }
 
     #[test]
-    fn test_count_keywords_for_all_reviewers() {
-        let context = get_context(true); // Simulate call by an author
+    fn ledger_records_fees_and_rewards() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string()); // Add an author for testing
-                                                           // Simulate the author submitting data
+        contract.set_submission_fee(U128(900), false);
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(900)
+            .account_balance(10_000)
             .build());
-        contract.add_reviewer(
-            "reviewer1.testnet".to_string(),
-            vec!["rust".to_string(), "smart contract".to_string()],
+        contract.submit_data("Test submission with a ledger".to_string());
+        let report = contract.get_treasury_report();
+        // The attached deposit is already part of the account balance
+        assert_eq!(report.account_balance, U128(10_900));
+        assert_eq!(report.escrow, U128(900));
+        assert_eq!(report.surplus, U128(10_000));
+        assert!(report.solvent);
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        assert_eq!(contract.get_treasury_report().escrow, U128(0));
+        let ledger = contract.get_submission_ledger(0, 0, 10);
+        assert_eq!(ledger.len(), 4);
+        assert_eq!(ledger[0].1.kind, LedgerKind::FeeCollected);
+        assert_eq!(
+            contract.get_account_ledger("reviewer2.testnet".to_string(), 0, 10),
+            vec![(3, ledger[3].1.clone())]
         );
-        contract.add_reviewer(
-            "reviewer2.testnet".to_string(),
-            vec!["blockchain".to_string(), "web3".to_string()],
+        // Pagination skips the first entries of the filtered ledger
+        assert_eq!(contract.get_submission_ledger(0, 3, 10).len(), 1);
+        assert_eq!(
+            contract.get_submission_totals(0, None),
+            vec![
+                (LedgerKind::FeeCollected, U128(900)),
+                (LedgerKind::RewardPaid, U128(900))
+            ]
         );
-        contract.add_reviewer("reviewer3.testnet".to_string(), vec!["rust".to_string()]);
-        contract.add_reviewer(
-            "reviewer4.testnet".to_string(),
-            vec!["smart contract".to_string(), "web3".to_string()],
+        assert_eq!(
+            contract.get_account_totals("author.testnet".to_string(), None),
+            vec![(LedgerKind::FeeCollected, U128(900))]
         );
+    }
 
-        // Verify the number of reviewers added
-        assert_eq!(contract.reviewers.len(), 4, "Should have 4 reviewers added");
-
-        let data = "This submission talks about rust and smart contracts in the context of blockchain and web3.".to_string();
-        // This part of the test remains unchanged as the modification in submit_data method
-        // now automatically handles the recording of suggested reviewers based on the keyword count.
-        // The assertions below ensure that the submit_data method's new behavior is correctly implemented.
-        contract.submit_data(data.clone());
-        assert_eq!(contract.submissions.len(), 1); // Verify submission was added
-        assert_eq!(contract.submissions[0].author, "author.testnet");
-        let submission = contract.submissions.last().unwrap();
-        let suggested_reviewers = &submission.suggested_reviewers;
-
+    #[test]
+    fn finalize_submission_score_aggregates() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_scoring_rubric(vec!["originality".to_string(), "rigor".to_string()]);
+        contract.submit_data("Test submission with scores".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        let ballots = [vec![8, 4], vec![6, 5], vec![9, 9]];
+        for (i, scores) in ballots.iter().enumerate() {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: "accept".to_string(),
+                    scores: scores.clone(),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        for (i, scores) in ballots.iter().enumerate() {
+            contract.reveal_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                Ballot {
+                    vote: "accept".to_string(),
+                    scores: scores.clone(),
+                    ..Default::default()
+                },
+                "secret".to_string(),
+            );
+        }
+        contract.finalize_submission(0);
         assert_eq!(
-            suggested_reviewers.len(),
-            3,
-            "Should have 3 suggested reviewers"
+            contract.get_score_aggregates(0),
+            vec![
+                CriterionAggregate {
+                    criterion: "originality".to_string(),
+                    mean: 766,
+                    median: 800,
+                    spread: 3,
+                },
+                CriterionAggregate {
+                    criterion: "rigor".to_string(),
+                    mean: 600,
+                    median: 500,
+                    spread: 5,
+                },
+            ]
         );
-        let top_reviewers = contract.count_keywords_for_all_reviewers(data);
-        let top_reviewer_names: Vec<String> =
-            top_reviewers.into_iter().map(|(name, _)| name).collect();
-        assert!(
-            suggested_reviewers
-                .iter()
-                .all(|reviewer| top_reviewer_names.contains(reviewer)),
-            "All suggested reviewers should be among the top reviewers"
+    }
+
+    #[test]
+    fn reveal_ballot_scores_out_of_range() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_scoring_rubric(vec!["clarity".to_string()]);
+        contract.submit_data("Test submission with invalid scores".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (0..3).map(|i| format!("reviewer{}.testnet", i)),
+        );
+        let ballot = Ballot {
+            vote: "accept".to_string(),
+            scores: vec![11],
+            ..Default::default()
+        };
+        for i in 0..3 {
+            set_signer(&format!("reviewer{}.testnet", i));
+            contract.commit_ballot(
+                0,
+                format!("reviewer{}.testnet", i),
+                ballot.clone(),
+                "secret".to_string(),
+            );
+        }
+        contract.end_voting(0);
+        contract.reveal_ballot(
+            0,
+            "reviewer0.testnet".to_string(),
+            ballot,
+            "secret".to_string(),
         );
+        assert!(contract.submissions[0]
+            .submission_votes
+            .revealed_votes
+            .is_empty());
     }
 
     #[test]
-    fn commit_vote_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "author.testnet".parse().unwrap();
+    fn issue_editor_decision_override() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
-        // Ensure the author is correctly recognized for submission
+        contract.add_track("economics".to_string());
+        contract.set_track_editor("economics".to_string(), Some("editor.testnet".to_string()));
+        contract.submit_to_track("economics".to_string(), "Test submission".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(true));
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
-            .signer_account_id("author.testnet".parse().unwrap())
+            .signer_account_id("editor.testnet".parse().unwrap())
             .build());
-        contract.submit_data("Test submission".to_string());
-        contract.commit_vote(
+        contract.issue_editor_decision(
             0,
-            "reviewer.testnet".to_string(),
-            "accept".to_string(),
-            "secret".to_string(),
-        );
-        assert_eq!(
-            contract.submissions[0].submission_votes.vote_commits.len(),
-            1
-        );
-        assert_eq!(
-            contract.submissions[0].submission_votes.vote_commits[0].reviewer,
-            "reviewer.testnet"
+            EditorVerdict::Reject,
+            "The proofs in section 3 do not hold.".to_string(),
         );
+        assert_eq!(contract.submissions[0].accepted, Some(false));
+        let decision = contract.get_editor_decision(0).unwrap();
+        assert_eq!(decision.editor, "editor.testnet");
+        assert!(decision.overrides_mechanical);
     }
 
     #[test]
-    #[should_panic(expected = "Submission not found.")]
-    fn commit_vote_submission_not_found() {
+    fn issue_editor_decision_not_editor() {
         let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.commit_vote(
-            1,
-            "reviewer.testnet".to_string(),
-            "accept".to_string(),
-            "secret".to_string(),
-        );
+        contract.submit_data("Test submission".to_string());
+        contract.set_submission_editor(0, Some("editor.testnet".to_string()));
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        // The owner is not the submission's editor
+        contract.issue_editor_decision(0, EditorVerdict::Reject, "Not convinced.".to_string());
+        assert_eq!(contract.submissions[0].accepted, Some(true));
+        assert!(contract.get_editor_decision(0).is_none());
     }
 
     #[test]
-    fn commit_vote_duplicate_vote() {
+    fn submit_revision_starts_new_round() {
         let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
-        contract.submit_data("Test submission".to_string());
-        contract.commit_vote(
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_data("First version".to_string());
+        contract.submissions[0].editor = Some("editor.testnet".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "reject", "reject"]);
+        contract.finalize_submission(0);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("editor.testnet".parse().unwrap())
+            .build());
+        contract.issue_editor_decision(
             0,
-            "reviewer.testnet".to_string(),
-            "accept".to_string(),
-            "secret".to_string(),
+            EditorVerdict::Revise,
+            "Please clarify the threat model.".to_string(),
         );
-        contract.commit_vote(
-            0,
-            "reviewer.testnet".to_string(),
-            "reject".to_string(),
-            "secret".to_string(),
+        assert!(contract.submissions[0].revisions_requested);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .build());
+        contract.submit_revision(0, "Second version".to_string());
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.round, 2);
+        assert_eq!(submission.response, "Second version");
+        assert!(!submission.voting_ended);
+        assert!(submission.submission_votes.vote_commits.is_empty());
+        // The first round stays queryable with its own text and votes
+        let first = contract.get_review_round(0, 1).unwrap();
+        assert_eq!(first.response, "First version");
+        assert_eq!(first.submission_votes.revealed_votes.len(), 3);
+        assert_eq!(
+            first.editor_decision.unwrap().verdict,
+            EditorVerdict::Revise
         );
         assert_eq!(
-            contract.submissions[0].submission_votes.vote_commits.len(),
-            1
-        ); // Expecting only one vote commit despite attempting to commit twice
+            contract.get_review_round(0, 2).unwrap().response,
+            "Second version"
+        );
+        // The same panel runs the new round
+        commit_and_reveal_votes(&mut contract, 0, &["accept", "accept", "accept"]);
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(true));
     }
 
     #[test]
-    fn end_voting_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "author.testnet".parse().unwrap();
+    fn appeal_overturns_rejection() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
+        for i in 0..9 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
             .build());
-        contract.submit_data("Test submission for voting".to_string());
-        // Simulate three reviewers committing their votes
-        for i in 0..3 {
-            contract.commit_vote(
+        contract.submit_data("A paper about rust".to_string());
+        // reviewer0..2 vote in the original round, so the appeal panel is drawn from the rest
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "reject"]);
+        contract.finalize_submission(0);
+        assert_eq!(contract.submissions[0].accepted, Some(false));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.file_appeal(0, "The reviewers misread the main theorem.".to_string());
+        let panel = contract.get_appeal(0).unwrap().panel;
+        assert_eq!(
+            panel,
+            vec![
+                "reviewer8.testnet".to_string(),
+                "reviewer7.testnet".to_string(),
+                "reviewer6.testnet".to_string()
+            ]
+        );
+        for reviewer in &panel {
+            set_signer(reviewer);
+            contract.commit_appeal_vote(
+                0,
+                reviewer.clone(),
+                "overturn".to_string(),
+                "secret".to_string(),
+            );
+        }
+        for reviewer in &panel {
+            set_signer(reviewer);
+            contract.reveal_appeal_vote(
                 0,
-                format!("reviewer{}.testnet", i),
-                "accept".to_string(),
+                reviewer.clone(),
+                "overturn".to_string(),
                 "secret".to_string(),
             );
         }
-        // Note: Direct log assertion is not supported with the current testing utilities
-        // The test will focus on the behavior that can be verified
-        testing_env!(VMContextBuilder::new()
-            .current_account_id(accounts(0))
-            .signer_account_id("author.testnet".parse().unwrap())
-            .build());
-        contract.end_voting(0);
-        assert!(
-            contract.submissions[0].voting_ended,
-            "Voting should be marked as ended."
-        );
+        contract.finalize_appeal(0);
+        let appeal = contract.get_appeal(0).unwrap();
+        assert_eq!(appeal.outcome, Some(AppealOutcome::Overturned));
+        assert_eq!(appeal.refunded, U128(1_000));
+        assert_eq!(contract.submissions[0].accepted, Some(true));
     }
 
     #[test]
-    fn reveal_vote_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
+    fn appeal_no_shows_count_as_abstentions() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
+        for i in 0..9 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
             .build());
-        contract.submit_data("Test submission for reveal".to_string());
-        contract.commit_vote(
+        contract.submit_data("A paper about rust".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "reject"]);
+        contract.finalize_submission(0);
+        contract.file_appeal(0, "The reviewers misread the main theorem.".to_string());
+        let panel = contract.get_appeal(0).unwrap().panel;
+        // Only one panel member shows up
+        set_signer(&panel[0]);
+        contract.commit_appeal_vote(
             0,
-            "reviewer1.testnet".to_string(),
-            "accept".to_string(),
-            "secret123".to_string(),
-        );
-        // Simulate three reviewers committing their votes
-        for i in 1..4 {
-            contract.commit_vote(
-                0,
-                format!("reviewer{}.testnet", i),
-                "accept".to_string(),
-                "secret123".to_string(),
-            );
-        }
-        // End voting after all reviewers have committed their votes
-        contract.end_voting(0);
-        assert!(
-            contract.submissions[0].voting_ended,
-            "Voting should be marked as ended."
+            panel[0].clone(),
+            "overturn".to_string(),
+            "secret".to_string(),
         );
-        // Now attempt to reveal a vote
-        contract.reveal_vote(
+        contract.reveal_appeal_vote(
             0,
-            "reviewer1.testnet".to_string(),
-            "accept".to_string(),
-            "secret123".to_string(),
-        );
-        assert_eq!(
-            contract.submissions[0]
-                .submission_votes
-                .revealed_votes
-                .get("reviewer1.testnet"),
-            Some(&"accept".to_string()),
-            "Vote should be revealed successfully."
+            panel[0].clone(),
+            "overturn".to_string(),
+            "secret".to_string(),
         );
-    }
-
-    #[test]
-    fn commit_comment_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
-        testing_env!(context);
-        let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
+        assert!(contract.get_appeal(0).unwrap().revealed_votes.is_empty());
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMIT_WINDOW + 1)
             .build());
-        contract.submit_data("Test submission for comment".to_string());
-        contract.commit_comment(
+        set_signer(&panel[1]);
+        contract.commit_appeal_vote(
             0,
-            "reviewer1.testnet".to_string(),
-            "Great proposal".to_string(),
-            "secret123".to_string(),
+            panel[1].clone(),
+            "uphold".to_string(),
+            "secret".to_string(),
         );
-        assert_eq!(
-            contract.submissions[0]
-                .submission_votes
-                .comment_commits
-                .len(),
-            1,
-            "Comment should be committed successfully."
+        set_signer(&panel[0]);
+        contract.reveal_appeal_vote(
+            0,
+            panel[0].clone(),
+            "overturn".to_string(),
+            "secret".to_string(),
         );
+        contract.finalize_appeal(0);
+        assert_eq!(contract.get_appeal(0).unwrap().outcome, None);
+        // Once reveals close, the missing votes are abstentions
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_COMMIT_WINDOW + DEFAULT_REVEAL_WINDOW + 1)
+            .build());
+        contract.finalize_appeal(0);
+        let appeal = contract.get_appeal(0).unwrap();
+        assert_eq!(appeal.vote_commits.len(), 1);
+        assert_eq!(appeal.outcome, Some(AppealOutcome::Overturned));
+        assert_eq!(contract.submissions[0].accepted, Some(true));
     }
 
     #[test]
-    fn reveal_comment_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "reviewer1.testnet".parse().unwrap();
+    fn upheld_appeal_keeps_deposit_in_treasury() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
+        for i in 0..9 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
             .build());
-        contract.submit_data("Test submission for reveal comment".to_string());
-        contract.commit_comment(
-            0,
-            "reviewer1.testnet".to_string(),
-            "Insightful analysis".to_string(),
-            "secret123".to_string(),
-        );
-        // Simulate three reviewers committing their votes
-        for i in 1..4 {
-            contract.commit_vote(
+        contract.submit_data("A paper about rust".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "reject"]);
+        contract.finalize_submission(0);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .account_balance(10_000)
+            .build());
+        contract.file_appeal(0, "The reviewers misread the main theorem.".to_string());
+        assert_eq!(contract.get_treasury_report().escrow, U128(1_000));
+        let panel = contract.get_appeal(0).unwrap().panel;
+        for reviewer in &panel {
+            set_signer(reviewer);
+            contract.commit_appeal_vote(
                 0,
-                format!("reviewer{}.testnet", i),
-                "accept".to_string(),
-                "secret123".to_string(),
+                reviewer.clone(),
+                "uphold".to_string(),
+                "secret".to_string(),
             );
         }
-        // End voting after all reviewers have committed their votes
-        contract.end_voting(0);
-        assert!(
-            contract.submissions[0].voting_ended,
-            "Voting should be marked as ended."
-        );
-        // Now attempt to reveal a comment
-        contract.reveal_comment(
-            0,
-            "reviewer1.testnet".to_string(),
-            "Insightful analysis".to_string(),
-            "secret123".to_string(),
-        );
+        for reviewer in &panel {
+            set_signer(reviewer);
+            contract.reveal_appeal_vote(
+                0,
+                reviewer.clone(),
+                "uphold".to_string(),
+                "secret".to_string(),
+            );
+        }
+        contract.finalize_appeal(0);
         assert_eq!(
-            contract.submissions[0]
-                .submission_votes
-                .revealed_comments
-                .get("reviewer1.testnet"),
-            Some(&"Insightful analysis".to_string()),
-            "Comment should be revealed successfully."
+            contract.get_appeal(0).unwrap().outcome,
+            Some(AppealOutcome::Upheld)
         );
+        let report = contract.get_treasury_report();
+        assert_eq!(report.escrow, U128(0));
+        assert_eq!(report.slashed, U128(1_000));
+        let ledger = contract.get_account_ledger("author.testnet".to_string(), 0, 10);
+        assert_eq!(ledger.last().unwrap().1.kind, LedgerKind::Slashed);
     }
 
     #[test]
-    fn finalize_submission_success() {
-        let mut context = get_context(true);
-        context.signer_account_id = "author.testnet".parse().unwrap();
+    fn appeal_votes_cast_by_the_author() {
+        let context = get_context(true);
         testing_env!(context);
         let mut contract = Contract::new();
-        contract.add_author("author.testnet".to_string());
+        for i in 0..9 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
         testing_env!(VMContextBuilder::new()
             .current_account_id(accounts(0))
             .signer_account_id("author.testnet".parse().unwrap())
             .build());
-        contract.submit_data("Test submission for finalization".to_string());
-        // Simulate three reviewers committing and revealing their votes
-        for i in 0..3 {
-            let reviewer = format!("reviewer{}.testnet", i);
-            contract.commit_vote(
-                0,
-                reviewer.clone(),
-                "accept".to_string(),
-                "secret".to_string(),
-            );
-            contract.reveal_vote(0, reviewer, "accept".to_string(), "secret".to_string());
-        }
-        contract.end_voting(0);
+        contract.submit_data("A paper about rust".to_string());
+        commit_and_reveal_votes(&mut contract, 0, &["reject", "reject", "reject"]);
         contract.finalize_submission(0);
-        assert_eq!(
-            contract.submissions[0].accepted,
-            Some(true),
-            "Submission should be accepted."
-        );
+        contract.file_appeal(0, "The reviewers misread the main theorem.".to_string());
+        let panel = contract.get_appeal(0).unwrap().panel;
+        // Neither the author nor an outsider can vote in a panel member's name
+        for signer in ["author.testnet", "outsider.testnet"] {
+            set_signer(signer);
+            for reviewer in &panel {
+                contract.commit_appeal_vote(
+                    0,
+                    reviewer.clone(),
+                    "overturn".to_string(),
+                    "secret".to_string(),
+                );
+            }
+        }
+        assert!(contract.get_appeal(0).unwrap().vote_commits.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only rejected submissions can be appealed.")]
+    fn file_appeal_not_rejected() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.submit_data("Undecided submission".to_string());
+        contract.file_appeal(0, "Please reconsider.".to_string());
     }
 
     #[test]
@@ -881,8 +6236,14 @@ mod tests {
             .signer_account_id("author.testnet".parse().unwrap())
             .build());
         contract.submit_data("Test submission for incorrect reveal".to_string());
+        seat_panel(
+            &mut contract,
+            0,
+            (1..4).map(|i| format!("reviewer{}.testnet", i)),
+        );
         // Simulate three reviewers committing their votes
         for i in 1..4 {
+            set_signer(&format!("reviewer{}.testnet", i));
             contract.commit_vote(
                 0,
                 format!("reviewer{}.testnet", i),
@@ -904,11 +6265,10 @@ mod tests {
             "secret123".to_string(),
         );
         assert!(
-            contract.submissions[0]
+            !contract.submissions[0]
                 .submission_votes
                 .revealed_votes
-                .get("reviewer1.testnet")
-                .is_none(),
+                .contains_key("reviewer1.testnet"),
             "Vote reveal should fail due to incorrect commit."
         );
     }
@@ -928,10 +6288,12 @@ mod tests {
                 vote_commits: vec![],
                 revealed_votes: HashMap::new(),
                 comment_commits: vec![],
-                revealed_comments: HashMap::new(),
+                revealed_comments: vec![],
+                ..Default::default()
             },
             voting_ended: true,
             accepted: Some(true),
+            ..Default::default()
         });
         contract.submissions.push(Submission {
             author: "author2.testnet".to_string(),
@@ -942,10 +6304,12 @@ mod tests {
                 vote_commits: vec![],
                 revealed_votes: HashMap::new(),
                 comment_commits: vec![],
-                revealed_comments: HashMap::new(),
+                revealed_comments: vec![],
+                ..Default::default()
             },
             voting_ended: true,
             accepted: Some(false),
+            ..Default::default()
         });
         contract.submissions.push(Submission {
             author: "author3.testnet".to_string(),
@@ -956,10 +6320,12 @@ mod tests {
                 vote_commits: vec![],
                 revealed_votes: HashMap::new(),
                 comment_commits: vec![],
-                revealed_comments: HashMap::new(),
+                revealed_comments: vec![],
+                ..Default::default()
             },
             voting_ended: true,
             accepted: None,
+            ..Default::default()
         });
         // Call get_accepted_submissions and verify the result
         let accepted_submissions = contract.get_accepted_submissions();
@@ -967,6 +6333,191 @@ mod tests {
         assert_eq!(accepted_submissions[0], "Accepted submission");
     }
 
+    #[test]
+    fn stake_required_for_assignment() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        for i in 0..4 {
+            contract.add_reviewer(format!("reviewer{}.testnet", i), vec!["rust".to_string()]);
+        }
+        contract.set_min_stake(U128(1_000));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.stake();
+        contract.submit_data("A paper about rust".to_string());
+        assert_eq!(
+            contract.submissions[0].suggested_reviewers,
+            vec!["reviewer1.testnet".to_string()]
+        );
+    }
+
+    #[test]
+    fn unstake_waits_for_unbonding_and_reports() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.add_reviewer("reviewer0.testnet".to_string(), vec![]);
+        contract.submit_data("Test submission".to_string());
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .attached_deposit(1_000)
+            .build());
+        contract.stake();
+        contract.request_unstake(U128(1_000));
+        let report_id = contract.report_misconduct(
+            "reviewer0.testnet".to_string(),
+            0,
+            "Plagiarized review.".to_string(),
+        );
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_UNBONDING_PERIOD)
+            .build());
+        // The pending report keeps the unbonded stake slashable
+        contract.withdraw_stake();
+        assert_eq!(contract.reviewers[0].unbonding, 1_000);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(accounts(0))
+            .block_timestamp(DEFAULT_UNBONDING_PERIOD)
+            .build());
+        contract.resolve_misconduct_report(report_id, true);
+        assert_eq!(contract.reviewers[0].unbonding, 900);
+        assert_eq!(contract.misconduct_reports[0].slashed, U128(100));
+        assert_eq!(contract.slashed_stake, 100);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer0.testnet".parse().unwrap())
+            .block_timestamp(DEFAULT_UNBONDING_PERIOD)
+            .build());
+        contract.withdraw_stake();
+        assert_eq!(contract.reviewers[0].unbonding, 0);
+    }
+
+    #[test]
+    fn storage_deposit_and_withdraw() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(0), true);
+        let author: AccountId = "author.testnet".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(author.clone())
+            .predecessor_account_id(author.clone())
+            .attached_deposit(10u128.pow(24))
+            .build());
+        contract.storage_deposit(None, None);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(author.clone())
+            .predecessor_account_id(author.clone())
+            .build());
+        // Persist the state so only the growth from the submission is measured
+        env::state_write(&contract);
+        let initial_storage = env::storage_usage();
+        contract.submit_data("A submission paid from the storage balance".to_string());
+        let used = contract.storage_used["author.testnet"];
+        assert_eq!(used, env::storage_usage() - initial_storage);
+        assert!(used >= contract.submissions[0].try_to_vec().unwrap().len() as u64);
+        assert_eq!(
+            contract.submissions[0].storage_cost,
+            used as u128 * env::storage_byte_cost()
+        );
+        let locked = STORAGE_BALANCE_MIN + used as u128 * env::storage_byte_cost();
+        let balance = contract.storage_balance_of(author.clone()).unwrap();
+        assert_eq!(balance.available, U128(10u128.pow(24) - locked));
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id(author.clone())
+            .predecessor_account_id(author.clone())
+            .attached_deposit(1)
+            .build());
+        let balance = contract.storage_withdraw(None);
+        assert_eq!(balance.total, U128(locked));
+        assert_eq!(balance.available, U128(0));
+    }
+
+    #[test]
+    fn add_keywords_to_reviewer_charges_storage_growth() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(0), true);
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec![]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .predecessor_account_id("reviewer1.testnet".parse().unwrap())
+            .attached_deposit(10u128.pow(24))
+            .build());
+        contract.storage_deposit(None, None);
+        env::state_write(&contract);
+        let initial_storage = env::storage_usage();
+        contract.add_keywords_to_reviewer(
+            "reviewer1.testnet".to_string(),
+            vec!["rust".to_string(), "wasm".to_string()],
+        );
+        assert_eq!(
+            contract.storage_used["reviewer1.testnet"],
+            env::storage_usage() - initial_storage
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient storage balance. Call storage_deposit first.")]
+    fn add_keywords_to_reviewer_without_storage_balance() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(0), true);
+        contract.add_reviewer("reviewer1.testnet".to_string(), vec![]);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("reviewer1.testnet".parse().unwrap())
+            .build());
+        contract
+            .add_keywords_to_reviewer("reviewer1.testnet".to_string(), vec!["rust".to_string()]);
+    }
+
+    #[test]
+    fn submit_data_refunds_excess_fee() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(1_000), false);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(10u128.pow(24))
+            .build());
+        contract.submit_data("A paid submission".to_string());
+        let submission = &contract.submissions[0];
+        assert_eq!(submission.review_fee, 1_000);
+        assert_eq!(submission.storage_cost, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attached deposit does not cover the submission fee.")]
+    fn submit_data_underpaid() {
+        let context = get_context(true);
+        testing_env!(context);
+        let mut contract = Contract::new();
+        contract.set_submission_fee(U128(1_000), false);
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(accounts(0))
+            .signer_account_id("author.testnet".parse().unwrap())
+            .attached_deposit(999)
+            .build());
+        contract.submit_data("An underpaid submission".to_string());
+    }
+
     #[test]
 
     fn submit_data_success() {